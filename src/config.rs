@@ -0,0 +1,280 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::smoothing::Smoothing;
+
+//A year, longer durations are typos
+const MAX_SECONDS: f64 = 365.0 * 24.0 * 3600.0;
+
+//Rusys configuration
+//Read once at startup from $RUSYS_CONFIG, or $XDG_CONFIG_HOME/rusys/rusys.conf,
+//or ~/.config/rusys/rusys.conf. Missing file == defaults.
+//
+//Format, one setting per line:
+//  # comment
+//  filesystems.exclude_types = proc, sysfs, tmpfs
+//  filesystems.warn_percent = 80
+//Lists are comma separated, patterns accept * and ? globs.
+//Unknown keys and unparsable values are ignored and keep their default.
+pub struct Config {
+    //Filesystem types hidden from the filesystems panel
+    pub fs_exclude_types: Vec<String>,
+    //Mount point globs hidden from the filesystems panel
+    pub fs_exclude_mounts: Vec<String>,
+    //Use% (space or inodes) colored as warning / alert
+    pub fs_warn_percent: f64,
+    pub fs_alert_percent: f64,
+    //Filesystems projected to be full within this many hours are highlighted
+    pub fs_full_horizon_hours: f64,
+    //Usage history the fill projection is fitted on
+    pub fs_trend_minutes: u64,
+    //Rate samples kept per interface for the network charts
+    pub net_history_length: usize,
+    //Interface globs shown in the network panel, empty == all
+    pub net_include: Vec<String>,
+    //Interface globs hidden from the network panel, applied after net_include
+    pub net_exclude: Vec<String>,
+    //Keep per-interface traffic totals in $XDG_DATA_HOME/rusys/traffic.tsv
+    pub net_accounting: bool,
+    //Monthly traffic per interface (rx + tx) warned about, 0 == no quota
+    pub net_monthly_quota_gb: f64,
+    //Network and disk rate smoothing: none, window:N or ewma:SECONDS
+    pub rate_smoothing: Smoothing,
+    //D-state lasting this long is reported
+    pub problems_uninterruptible_seconds: f64,
+    //Percent of one core a process must hold for problems_runaway_seconds to be reported
+    pub problems_runaway_cpu: f32,
+    pub problems_runaway_seconds: f64,
+    //A condition cleared for less than this still counts as ongoing
+    pub problems_grace_seconds: f64,
+    //How often /proc is listed for process starts and exits. Every poll reads the stat file
+    //of every process: lower catches shorter-lived processes and costs more CPU
+    pub events_poll_ms: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            fs_exclude_types: to_list(
+                "proc, sysfs, devtmpfs, devpts, tmpfs, ramfs, cgroup, cgroup2, securityfs, pstore, \
+                 bpf, debugfs, tracefs, configfs, fusectl, mqueue, hugetlbfs, autofs, binfmt_misc, \
+                 rpc_pipefs, nsfs, efivarfs, selinuxfs, overlay, squashfs, fuse.portal, fuse.gvfsd-fuse",
+            ),
+            fs_exclude_mounts: to_list("/proc/*, /sys/*, /dev/*, /run/*, /snap/*, /var/lib/docker/*"),
+            fs_warn_percent: 80.0,
+            fs_alert_percent: 90.0,
+            fs_full_horizon_hours: 24.0,
+            fs_trend_minutes: 30,
+            net_history_length: 240,
+            net_include: Vec::new(),
+            net_exclude: to_list("docker*, veth*, br-*, virbr*, tun*, vmnet*, vboxnet*, dummy*"),
+            net_accounting: true,
+            net_monthly_quota_gb: 0.0,
+            rate_smoothing: Smoothing::Window(3),
+            problems_uninterruptible_seconds: 5.0,
+            problems_runaway_cpu: 90.0,
+            problems_runaway_seconds: 15.0,
+            problems_grace_seconds: 5.0,
+            events_poll_ms: 50,
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let content = config_path().and_then(|path| fs::read_to_string(path).ok()).unwrap_or_default();
+        Config::parse(&content)
+    }
+
+    fn parse(content: &str) -> Self {
+        let mut config = Config::default();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                config.set(key.trim(), value.trim());
+            }
+        }
+        config
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "filesystems.exclude_types" => self.fs_exclude_types = to_list(value),
+            "filesystems.exclude_mounts" => self.fs_exclude_mounts = to_list(value),
+            "filesystems.warn_percent" => set_parsed(&mut self.fs_warn_percent, value),
+            "filesystems.alert_percent" => set_parsed(&mut self.fs_alert_percent, value),
+            "filesystems.full_horizon_hours" => set_parsed(&mut self.fs_full_horizon_hours, value),
+            "filesystems.trend_minutes" => set_parsed(&mut self.fs_trend_minutes, value),
+            "network.history_length" => set_parsed(&mut self.net_history_length, value),
+            "network.include" => self.net_include = to_list(value),
+            "network.exclude" => self.net_exclude = to_list(value),
+            "network.accounting" => set_parsed(&mut self.net_accounting, value),
+            "network.monthly_quota_gb" => set_parsed(&mut self.net_monthly_quota_gb, value),
+            "rates.smoothing" => set_parsed(&mut self.rate_smoothing, value),
+            "problems.uninterruptible_seconds" => set_seconds(&mut self.problems_uninterruptible_seconds, value),
+            "problems.runaway_cpu" => set_parsed(&mut self.problems_runaway_cpu, value),
+            "problems.runaway_seconds" => set_seconds(&mut self.problems_runaway_seconds, value),
+            "problems.grace_seconds" => set_seconds(&mut self.problems_grace_seconds, value),
+            "events.poll_ms" => set_parsed(&mut self.events_poll_ms, value),
+            _ => {}
+        }
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("RUSYS_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("rusys").join("rusys.conf"))
+}
+
+//State kept across runs: $XDG_DATA_HOME/rusys/<file> or ~/.local/share/rusys/<file>
+pub fn data_path(file: &str) -> Option<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))?;
+    Some(base.join("rusys").join(file))
+}
+
+//Keep the default when the value doesn't parse
+fn set_parsed<T: std::str::FromStr>(target: &mut T, value: &str) {
+    if let Ok(parsed) = value.parse() {
+        *target = parsed;
+    }
+}
+
+//Durations end up in Duration::from_secs_f64, which panics on negative or huge values
+fn set_seconds(target: &mut f64, value: &str) {
+    if let Ok(seconds) = value.parse::<f64>() {
+        if (0.0..=MAX_SECONDS).contains(&seconds) {
+            *target = seconds;
+        }
+    }
+}
+
+fn to_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+//Shell-like glob: * any run of characters, ? exactly one
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    //Last * seen and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                //Let the star eat one more character
+                Some((star, start)) => {
+                    p = star + 1;
+                    t = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_star_matches_anything() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "eth0"));
+        assert!(glob_match("**", "eth0"));
+    }
+
+    #[test]
+    fn glob_trailing_star() {
+        assert!(glob_match("veth*", "veth"));
+        assert!(glob_match("veth*", "veth1a2b"));
+        assert!(!glob_match("veth*", "eth0"));
+        assert!(!glob_match("veth*", "xveth0"));
+    }
+
+    #[test]
+    fn glob_without_wildcard_is_exact() {
+        assert!(glob_match("/proc", "/proc"));
+        assert!(!glob_match("/proc", "/proc/1"));
+        assert!(!glob_match("/proc", "/pro"));
+        assert!(!glob_match("", "a"));
+        assert!(glob_match("", ""));
+    }
+
+    #[test]
+    fn glob_question_mark_and_backtracking() {
+        assert!(glob_match("tun?", "tun0"));
+        assert!(!glob_match("tun?", "tun"));
+        assert!(!glob_match("tun?", "tun10"));
+        assert!(glob_match("/run/*/gvfs", "/run/user/1000/gvfs"));
+        assert!(glob_match("*.*.b", "a.b.c.b"));
+        assert!(!glob_match("*a*b", "xaxc"));
+    }
+
+    #[test]
+    fn parse_skips_comments_and_trims() {
+        let config = Config::parse(
+            "# comment line\n\
+             \n\
+             \t  filesystems.warn_percent   =  70 \n\
+             network.exclude = veth* ,  , docker*\n\
+             #network.accounting = false\n\
+             rates.smoothing=ewma:5s\n",
+        );
+        assert_eq!(config.fs_warn_percent, 70.0);
+        assert_eq!(config.net_exclude, vec!["veth*", "docker*"]);
+        assert!(config.net_accounting);
+        assert!(config.rate_smoothing == Smoothing::Ewma { half_life: 5.0 });
+    }
+
+    #[test]
+    fn invalid_values_keep_defaults() {
+        let defaults = Config::default();
+        let config = Config::parse(
+            "filesystems.warn_percent = lots\n\
+             filesystems.trend_minutes = -3\n\
+             network.accounting = maybe\n\
+             rates.smoothing = window:0\n\
+             problems.grace_seconds = -1\n\
+             no equals sign here\n\
+             unknown.key = 1\n",
+        );
+        assert_eq!(config.fs_warn_percent, defaults.fs_warn_percent);
+        assert_eq!(config.fs_trend_minutes, defaults.fs_trend_minutes);
+        assert_eq!(config.net_accounting, defaults.net_accounting);
+        assert!(config.rate_smoothing == defaults.rate_smoothing);
+        assert_eq!(config.problems_grace_seconds, defaults.problems_grace_seconds);
+    }
+
+    #[test]
+    fn value_may_contain_equals() {
+        let config = Config::parse("filesystems.exclude_mounts = /a=b/*, /c\n");
+        assert_eq!(config.fs_exclude_mounts, vec!["/a=b/*", "/c"]);
+    }
+}
@@ -1,5 +1,8 @@
+mod config;
 mod monitoring;
 mod network;
+mod smoothing;
+mod storage;
 mod syst;
 mod ui;
 
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nix::unistd::{sysconf, SysconfVar};
+
+//Events kept in the rolling log, oldest dropped first
+const MAX_EVENTS: usize = 500;
+//Faster polling than this costs more than it catches, see events.poll_ms in Config
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LifecycleKind {
+    Started,
+    Exited,
+}
+
+pub struct LifecycleEvent {
+    pub kind: LifecycleKind,
+    //When the poller noticed it
+    pub at: Instant,
+    pub pid: u32,
+    pub parent: Option<u32>,
+    pub command: String,
+    //Only known once the process exited, to the clock tick (10 ms on most systems)
+    pub lifetime: Option<Duration>,
+    //Highest values seen while the process was alive, sampled every poll
+    pub peak_cpu: f32,
+    pub peak_memory: u64,
+}
+
+//Rolling log of process starts and exits
+//A dedicated thread polls /proc much faster than the UI ticks. A process living less than
+//the poll interval can be missed entirely, and every poll reads /proc/<pid>/stat of every
+//process: with 2000 processes, 50 ms is 40000 reads a second.
+pub struct LifecycleMonitor {
+    events: VecDeque<LifecycleEvent>,
+    receiver: Receiver<LifecycleEvent>,
+    stop: Arc<AtomicBool>,
+    interval: Duration,
+}
+
+impl LifecycleMonitor {
+    pub fn new(interval: Duration) -> Self {
+        let interval = interval.max(MIN_POLL_INTERVAL);
+        let (sender, receiver) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let poller_stop = Arc::clone(&stop);
+        thread::spawn(move || poll(&sender, &poller_stop, interval));
+        LifecycleMonitor {
+            events: VecDeque::new(),
+            receiver,
+            stop,
+            interval,
+        }
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.interval
+    }
+
+    //Newest first
+    pub fn events(&self) -> impl Iterator<Item = &LifecycleEvent> {
+        self.events.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    //Collect what the poller saw since the last call, returns how many events were added
+    pub fn update(&mut self) -> usize {
+        let mut added = 0;
+        for event in self.receiver.try_iter() {
+            self.events.push_back(event);
+            if self.events.len() > MAX_EVENTS {
+                self.events.pop_front();
+            }
+            added += 1;
+        }
+        added
+    }
+}
+
+impl Drop for LifecycleMonitor {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+//What the poller remembers about a live process between polls
+struct Tracked {
+    parent: Option<u32>,
+    command: String,
+    //Clock ticks after boot, tells a reused PID apart from the old one
+    start_ticks: u64,
+    //utime + stime at the last poll
+    cpu_ticks: u64,
+    peak_cpu: f32,
+    peak_memory: u64,
+}
+
+//Fields of /proc/<pid>/stat we need
+struct ProcStat {
+    name: String,
+    parent: Option<u32>,
+    cpu_ticks: u64,
+    start_ticks: u64,
+    //Resident pages
+    rss: u64,
+}
+
+impl ProcStat {
+    fn read(pid: u32) -> Option<Self> {
+        ProcStat::parse(&fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?)
+    }
+
+    //pid (comm) state ppid ... utime stime ... starttime vsize rss ...
+    //comm can hold spaces and parentheses, fields are counted from the last ')'
+    fn parse(content: &str) -> Option<Self> {
+        let open = content.find('(')?;
+        let close = content.rfind(')')?;
+        let fields: Vec<&str> = content.get(close + 1..)?.split_whitespace().collect();
+        let field = |index: usize| fields.get(index).and_then(|value| value.parse::<u64>().ok());
+        Some(ProcStat {
+            name: content.get(open + 1..close)?.to_string(),
+            parent: field(1).filter(|&ppid| ppid != 0).map(|ppid| ppid as u32),
+            cpu_ticks: field(11)? + field(12)?,
+            start_ticks: field(19)?,
+            rss: field(21)?,
+        })
+    }
+}
+
+fn poll(sender: &Sender<LifecycleEvent>, stop: &AtomicBool, interval: Duration) {
+    let ticks_per_second = sysconf(SysconfVar::CLK_TCK).ok().flatten().unwrap_or(100).max(1) as f64;
+    let page_size = sysconf(SysconfVar::PAGE_SIZE).ok().flatten().unwrap_or(4096) as u64;
+    //-> u32 == PID
+    let mut tracked: HashMap<u32, Tracked> = HashMap::new();
+    //First listing only fills `tracked`, everything would look "started" otherwise
+    let mut primed = false;
+    let mut last_poll = Instant::now();
+
+    while !stop.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(last_poll).as_secs_f64().max(1.0 / ticks_per_second);
+        last_poll = now;
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return;
+        };
+        let uptime = read_uptime();
+        let mut events = Vec::new();
+        let mut alive: HashMap<u32, Tracked> = HashMap::with_capacity(tracked.len());
+
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+            //Exited between the listing and the read
+            let Some(stat) = ProcStat::read(pid) else {
+                continue;
+            };
+            let memory = stat.rss * page_size;
+            let previous = match tracked.remove(&pid) {
+                Some(process) if process.start_ticks == stat.start_ticks => Some(process),
+                //Same PID but another start time: old one exited, PID got reused
+                Some(process) => {
+                    events.push(exited(pid, process, now, uptime, ticks_per_second));
+                    None
+                }
+                None => None,
+            };
+            let process = match previous {
+                Some(mut process) => {
+                    let cpu = stat.cpu_ticks.saturating_sub(process.cpu_ticks) as f64 / ticks_per_second / elapsed;
+                    process.cpu_ticks = stat.cpu_ticks;
+                    process.peak_cpu = process.peak_cpu.max((cpu * 100.0) as f32);
+                    process.peak_memory = process.peak_memory.max(memory);
+                    process
+                }
+                None => {
+                    //Nothing to diff against yet, average over the whole life so far
+                    let age = (uptime - stat.start_ticks as f64 / ticks_per_second).max(1.0 / ticks_per_second);
+                    let cpu = stat.cpu_ticks as f64 / ticks_per_second / age;
+                    let process = Tracked {
+                        parent: stat.parent,
+                        command: read_command(pid, &stat.name),
+                        start_ticks: stat.start_ticks,
+                        cpu_ticks: stat.cpu_ticks,
+                        peak_cpu: (cpu * 100.0) as f32,
+                        peak_memory: memory,
+                    };
+                    if primed {
+                        events.push(LifecycleEvent {
+                            kind: LifecycleKind::Started,
+                            at: now,
+                            pid,
+                            parent: process.parent,
+                            command: process.command.clone(),
+                            lifetime: None,
+                            peak_cpu: process.peak_cpu,
+                            peak_memory: process.peak_memory,
+                        });
+                    }
+                    process
+                }
+            };
+            alive.insert(pid, process);
+        }
+
+        //Whatever is left was not in this listing anymore
+        let mut gone: Vec<(u32, Tracked)> = tracked.drain().collect();
+        gone.sort_by_key(|(pid, _)| *pid);
+        for (pid, process) in gone {
+            events.push(exited(pid, process, now, uptime, ticks_per_second));
+        }
+        tracked = alive;
+        primed = true;
+
+        for event in events {
+            //UI is gone
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn exited(pid: u32, process: Tracked, now: Instant, uptime: f64, ticks_per_second: f64) -> LifecycleEvent {
+    LifecycleEvent {
+        kind: LifecycleKind::Exited,
+        at: now,
+        pid,
+        parent: process.parent,
+        command: process.command,
+        lifetime: Duration::try_from_secs_f64(uptime - process.start_ticks as f64 / ticks_per_second).ok(),
+        peak_cpu: process.peak_cpu,
+        peak_memory: process.peak_memory,
+    }
+}
+
+//Seconds since boot, same clock as the stat start times
+fn read_uptime() -> f64 {
+    fs::read_to_string("/proc/uptime")
+        .ok()
+        .and_then(|content| content.split_whitespace().next()?.parse().ok())
+        .unwrap_or(0.0)
+}
+
+//Full command line, [name] for kernel threads like the process table
+fn read_command(pid: u32, name: &str) -> String {
+    let command = fs::read(format!("/proc/{}/cmdline", pid))
+        .map(|raw| {
+            raw.split(|&byte| byte == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default();
+    if command.is_empty() {
+        format!("[{}]", name)
+    } else {
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stat_fields_after_comm() {
+        let stat = ProcStat::parse(
+            "4242 (bash) S 4200 4242 4242 34816 4300 4194304 1200 3400 0 0 \
+             17 5 0 0 20 0 1 0 98765 12345678 890 18446744073709551615 1 1 0 0 0 0 0\n",
+        )
+        .unwrap();
+        assert_eq!(stat.name, "bash");
+        assert_eq!(stat.parent, Some(4200));
+        assert_eq!(stat.cpu_ticks, 22);
+        assert_eq!(stat.start_ticks, 98765);
+        assert_eq!(stat.rss, 890);
+    }
+
+    #[test]
+    fn comm_with_closing_parenthesis() {
+        let stat = ProcStat::parse(
+            "77 (evil) S 1 (x) R 1 77 77 0 -1 4194560 10 0 0 0 3 4 0 0 20 0 1 0 555 1000 42 0 0 0\n",
+        )
+        .unwrap();
+        assert_eq!(stat.name, "evil) S 1 (x");
+        assert_eq!(stat.parent, Some(1));
+        assert_eq!(stat.cpu_ticks, 7);
+        assert_eq!(stat.start_ticks, 555);
+        assert_eq!(stat.rss, 42);
+    }
+
+    #[test]
+    fn kernel_thread_has_no_parent() {
+        let stat = ProcStat::parse("2 (kthreadd) S 0 0 0 0 -1 2129984 0 0 0 0 0 1 0 0 20 0 1 0 2 0 0 0\n").unwrap();
+        assert_eq!(stat.parent, None);
+    }
+
+    #[test]
+    fn truncated_stat() {
+        assert!(ProcStat::parse("4242 (bash) S 4200 4242 4242 34816 4300").is_none());
+        assert!(ProcStat::parse("4242 (bash").is_none());
+        assert!(ProcStat::parse("").is_none());
+    }
+}
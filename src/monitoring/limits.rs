@@ -0,0 +1,157 @@
+use std::fs;
+use std::time::{Duration, Instant};
+
+use super::process::ProcessInfo;
+
+//Share of a limit where we start warning, and where it gets urgent
+pub const WARN_RATIO: f64 = 0.8;
+pub const ALERT_RATIO: f64 = 0.95;
+//Processes listed in the fd section, biggest consumers first
+const TOP_FD_PROCESSES: usize = 10;
+//Counting every fd of every process is not free, and limits are approached slowly
+const REFRESH_EVERY: Duration = Duration::from_secs(5);
+
+//One resource compared to its limit
+pub struct LimitUsage {
+    //What runs out, ex: "open files"
+    pub resource: &'static str,
+    //Who it belongs to, "system" or "name (pid)"
+    pub owner: String,
+    pub used: u64,
+    pub limit: u64,
+}
+
+impl LimitUsage {
+    pub fn ratio(&self) -> f64 {
+        if self.limit == 0 {
+            return 0.0;
+        }
+        self.used as f64 / self.limit as f64
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.ratio() >= WARN_RATIO
+    }
+}
+
+//Limit usage, recomputed every REFRESH_EVERY
+pub struct LimitMonitor {
+    last_refresh: Option<Instant>,
+}
+
+impl LimitMonitor {
+    pub fn new() -> Self {
+        LimitMonitor { last_refresh: None }
+    }
+
+    //None when the last numbers are recent enough to keep
+    pub fn get_limit_usage(&mut self, processes: &[ProcessInfo]) -> Option<Vec<LimitUsage>> {
+        let now = Instant::now();
+        if self.last_refresh.is_some_and(|last| now.duration_since(last) < REFRESH_EVERY) {
+            return None;
+        }
+        self.last_refresh = Some(now);
+        Some(limit_usage(processes))
+    }
+}
+
+//System-wide tables first, then the processes closest to RLIMIT_NOFILE
+//Processes we can't inspect (other users without root) are skipped
+fn limit_usage(processes: &[ProcessInfo]) -> Vec<LimitUsage> {
+    let mut usage = Vec::new();
+
+    //allocated, unused (always 0 since 2.6), max
+    if let Some(fields) = read_fields("/proc/sys/fs/file-nr") {
+        if let [allocated, unused, max, ..] = fields[..] {
+            usage.push(LimitUsage {
+                resource: "open files",
+                owner: "system".to_string(),
+                used: allocated.saturating_sub(unused),
+                limit: max,
+            });
+        }
+    }
+
+    //Every thread takes a PID
+    let tasks = fs::read_to_string("/proc/loadavg").ok().and_then(|loadavg| task_count(&loadavg));
+    if let Some(tasks) = tasks {
+        for (resource, path) in [("pids", "/proc/sys/kernel/pid_max"), ("threads", "/proc/sys/kernel/threads-max")] {
+            if let Some(&[limit, ..]) = read_fields(path).as_deref() {
+                usage.push(LimitUsage {
+                    resource,
+                    owner: "system".to_string(),
+                    used: tasks,
+                    limit,
+                });
+            }
+        }
+    }
+
+    let mut per_process: Vec<LimitUsage> = processes
+        .iter()
+        .filter_map(|process| {
+            let used = fs::read_dir(format!("/proc/{}/fd", process.pid)).ok()?.count() as u64;
+            let limit = nofile_limit(&fs::read_to_string(format!("/proc/{}/limits", process.pid)).ok()?)?;
+            Some(LimitUsage {
+                resource: "fds",
+                owner: format!("{} ({})", process.name, process.pid),
+                used,
+                limit,
+            })
+        })
+        .collect();
+    per_process.sort_by(|a, b| b.ratio().total_cmp(&a.ratio()));
+    per_process.truncate(TOP_FD_PROCESSES);
+    usage.extend(per_process);
+    usage
+}
+
+//4th loadavg field is "running/total" tasks: 0.12 0.08 0.05 2/345 6789
+fn task_count(loadavg: &str) -> Option<u64> {
+    loadavg.split_whitespace().nth(3)?.split('/').nth(1)?.parse().ok()
+}
+
+//Soft RLIMIT_NOFILE from /proc/<pid>/limits, None when unlimited
+fn nofile_limit(limits: &str) -> Option<u64> {
+    limits
+        .lines()
+        .find_map(|line| line.strip_prefix("Max open files")?.split_whitespace().next()?.parse().ok())
+}
+
+//Whitespace separated numbers of a /proc file
+fn read_fields(path: &str) -> Option<Vec<u64>> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| content.split_whitespace().filter_map(|field| field.parse().ok()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: &str = "\
+Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max processes             63345                63345                processes
+Max open files            1024                 524288               files
+Max locked memory         8388608              8388608              bytes
+";
+
+    #[test]
+    fn nofile_soft_limit() {
+        assert_eq!(nofile_limit(LIMITS), Some(1024));
+    }
+
+    #[test]
+    fn nofile_unlimited_or_missing() {
+        assert_eq!(nofile_limit(&LIMITS.replace("1024 ", "unlimited")), None);
+        assert_eq!(nofile_limit("Limit Soft Limit Hard Limit Units\n"), None);
+    }
+
+    #[test]
+    fn loadavg_task_count() {
+        assert_eq!(task_count("0.12 0.08 0.05 2/345 6789\n"), Some(345));
+        assert_eq!(task_count("0.12 0.08 0.05"), None);
+        assert_eq!(task_count("0.12 0.08 0.05 2-345 6789"), None);
+    }
+}
@@ -1 +1,7 @@
 pub mod cpu;
+pub mod process;
+pub mod systemd;
+pub mod lifecycle;
+pub mod problems;
+pub mod limits;
+pub mod pinned;
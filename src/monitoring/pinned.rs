@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::time::Instant;
+
+use super::process::ProcessInfo;
+
+//Samples kept per pin, one per tick
+const HISTORY_LEN: usize = 240;
+
+//How a pin finds its process again
+pub enum PinPattern {
+    //Exact process name
+    Name(String),
+    //Substring of the full command line
+    Command(String),
+}
+
+impl PinPattern {
+    fn matches(&self, process: &ProcessInfo) -> bool {
+        match self {
+            PinPattern::Name(name) => &process.name == name,
+            PinPattern::Command(pattern) => process.command.contains(pattern.as_str()),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            PinPattern::Name(name) => format!("name={}", name),
+            PinPattern::Command(pattern) => format!("cmd~{}", pattern),
+        }
+    }
+}
+
+pub struct PinSample {
+    pub cpu_usage: f32,
+    pub memory: u64,
+    //Bytes per second
+    pub read_rate: f64,
+    pub write_rate: f64,
+    //None when /proc/<pid>/fd isn't readable
+    pub fds: Option<u64>,
+}
+
+pub struct PinnedProcess {
+    pub pattern: PinPattern,
+    //Process currently followed, None while it is down
+    pub pid: Option<u32>,
+    start_time: u64,
+    //Every time a new instance was picked up after the first one
+    pub restarts: Vec<Instant>,
+    pub attached_at: Option<Instant>,
+    //Gaps while the process is down are not recorded
+    pub history: VecDeque<PinSample>,
+    //Cumulative read/written bytes of the previous tick
+    last_io: Option<(u64, u64)>,
+}
+
+impl PinnedProcess {
+    fn new(pattern: PinPattern) -> Self {
+        PinnedProcess {
+            pattern,
+            pid: None,
+            start_time: 0,
+            restarts: Vec::new(),
+            attached_at: None,
+            history: VecDeque::new(),
+            last_io: None,
+        }
+    }
+}
+
+pub struct PinMonitor {
+    pub pins: Vec<PinnedProcess>,
+    last_update: Instant,
+}
+
+impl PinMonitor {
+    pub fn new() -> Self {
+        PinMonitor {
+            pins: Vec::new(),
+            last_update: Instant::now(),
+        }
+    }
+
+    pub fn pin(&mut self, pattern: PinPattern) {
+        let description = pattern.describe();
+        if self.pins.iter().all(|pin| pin.pattern.describe() != description) {
+            self.pins.push(PinnedProcess::new(pattern));
+        }
+    }
+
+    pub fn unpin(&mut self, index: usize) {
+        if index < self.pins.len() {
+            self.pins.remove(index);
+        }
+    }
+
+    //Follow each pin to its process, re-attaching by pattern when the PID went away
+    pub fn update(&mut self, processes: &[ProcessInfo]) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+
+        for pin in &mut self.pins {
+            let current = pin.pid.and_then(|pid| {
+                processes
+                    .iter()
+                    .find(|process| process.pid == pid && process.start_time == pin.start_time)
+            });
+            let process = match current {
+                Some(process) => Some(process),
+                None => {
+                    //Oldest match is usually the parent/master process
+                    let candidate = processes
+                        .iter()
+                        .filter(|process| pin.pattern.matches(process))
+                        .min_by_key(|process| (process.start_time, process.pid));
+                    if let Some(process) = candidate {
+                        if pin.attached_at.is_some() {
+                            pin.restarts.push(now);
+                        }
+                        pin.attached_at = Some(now);
+                        pin.start_time = process.start_time;
+                        pin.last_io = None;
+                    }
+                    candidate
+                }
+            };
+            pin.pid = process.map(|process| process.pid);
+
+            let Some(process) = process else {
+                continue;
+            };
+            let (read_rate, write_rate) = match pin.last_io {
+                Some((last_read, last_written)) if elapsed > 0.0 => (
+                    process.read_bytes.saturating_sub(last_read) as f64 / elapsed,
+                    process.written_bytes.saturating_sub(last_written) as f64 / elapsed,
+                ),
+                _ => (0.0, 0.0),
+            };
+            pin.last_io = Some((process.read_bytes, process.written_bytes));
+
+            pin.history.push_back(PinSample {
+                cpu_usage: process.cpu_usage,
+                memory: process.memory,
+                read_rate,
+                write_rate,
+                fds: fs::read_dir(format!("/proc/{}/fd", process.pid))
+                    .ok()
+                    .map(|entries| entries.count() as u64),
+            });
+            if pin.history.len() > HISTORY_LEN {
+                pin.history.pop_front();
+            }
+        }
+        self.last_update = now;
+    }
+}
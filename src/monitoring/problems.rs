@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use sysinfo::ProcessStatus;
+
+use crate::config::Config;
+use super::process::ProcessInfo;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProblemKind {
+    Zombie,
+    //Stuck in uninterruptible sleep (D)
+    Uninterruptible,
+    //CPU above problems.runaway_cpu the whole time
+    Runaway,
+}
+
+impl ProblemKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProblemKind::Zombie => "zombie",
+            ProblemKind::Uninterruptible => "D-state",
+            ProblemKind::Runaway => "runaway",
+        }
+    }
+}
+
+pub struct Problem {
+    pub kind: ProblemKind,
+    pub pid: u32,
+    pub name: String,
+    //Parent PID and name, the one that has to reap a zombie
+    pub parent: Option<(u32, String)>,
+    //How long the condition has lasted
+    pub duration: Duration,
+    pub cpu_usage: f32,
+}
+
+//When a condition started and when it was last true
+#[derive(Clone, Copy)]
+struct Onset {
+    since: Instant,
+    last_seen: Instant,
+}
+
+pub struct ProblemDetector {
+    //-> (PID, start time, kind) == condition still held or within the grace period
+    onsets: HashMap<(u32, u64, ProblemKind), Onset>,
+    //See problems.* in Config
+    uninterruptible_after: Duration,
+    runaway_cpu: f32,
+    runaway_after: Duration,
+    grace: Duration,
+}
+
+impl ProblemDetector {
+    pub fn new(config: &Config) -> Self {
+        ProblemDetector {
+            onsets: HashMap::new(),
+            uninterruptible_after: Duration::from_secs_f64(config.problems_uninterruptible_seconds),
+            runaway_cpu: config.problems_runaway_cpu,
+            runaway_after: Duration::from_secs_f64(config.problems_runaway_seconds),
+            grace: Duration::from_secs_f64(config.problems_grace_seconds),
+        }
+    }
+
+    //How long the condition must hold before it is reported
+    fn threshold(&self, kind: ProblemKind) -> Duration {
+        match kind {
+            ProblemKind::Zombie => Duration::ZERO,
+            //D-state for a moment is normal I/O, only report it when it sticks
+            ProblemKind::Uninterruptible => self.uninterruptible_after,
+            ProblemKind::Runaway => self.runaway_after,
+        }
+    }
+
+    fn matches(&self, kind: ProblemKind, process: &ProcessInfo) -> bool {
+        match kind {
+            ProblemKind::Zombie => process.status == ProcessStatus::Zombie,
+            ProblemKind::Uninterruptible => process.status == ProcessStatus::UninterruptibleDiskSleep,
+            ProblemKind::Runaway => process.cpu_usage >= self.runaway_cpu,
+        }
+    }
+
+    //Track every condition and return the ones that lasted long enough, longest first
+    pub fn update(&mut self, processes: &[ProcessInfo]) -> Vec<Problem> {
+        let now = Instant::now();
+        let kinds = [ProblemKind::Zombie, ProblemKind::Uninterruptible, ProblemKind::Runaway];
+        let names: HashMap<u32, &str> = processes
+            .iter()
+            .map(|process| (process.pid, process.name.as_str()))
+            .collect();
+
+        let mut onsets = HashMap::new();
+        let mut problems = Vec::new();
+        for process in processes {
+            for kind in kinds {
+                let key = (process.pid, process.start_time, kind);
+                let onset = match (self.matches(kind, process), self.onsets.get(&key).copied()) {
+                    (true, Some(onset)) => Onset { last_seen: now, ..onset },
+                    (true, None) => Onset { since: now, last_seen: now },
+                    //A short dip (88% on a 90% threshold) doesn't restart the clock
+                    (false, Some(onset)) if now.duration_since(onset.last_seen) < self.grace => onset,
+                    (false, _) => continue,
+                };
+                onsets.insert(key, onset);
+
+                let duration = now.duration_since(onset.since);
+                if duration < self.threshold(kind) {
+                    continue;
+                }
+                problems.push(Problem {
+                    kind,
+                    pid: process.pid,
+                    name: process.name.clone(),
+                    parent: process.parent.map(|pid| {
+                        (pid, names.get(&pid).map_or("?".to_string(), |name| name.to_string()))
+                    }),
+                    duration,
+                    cpu_usage: process.cpu_usage,
+                });
+            }
+        }
+
+        //Conditions cleared for longer than the grace period are forgotten, they start over if they come back
+        self.onsets = onsets;
+        problems.sort_by(|a, b| b.duration.cmp(&a.duration).then(a.pid.cmp(&b.pid)));
+        problems
+    }
+}
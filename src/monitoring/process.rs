@@ -0,0 +1,54 @@
+use sysinfo::{ProcessStatus, System};
+
+//One row of the process table
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub parent: Option<u32>,
+    pub name: String,
+    //Full command line, falls back to name for kernel threads
+    pub command: String,
+    pub status: ProcessStatus,
+    //Percent of one core, can go above 100
+    pub cpu_usage: f32,
+    //Resident memory in bytes
+    pub memory: u64,
+    //Seconds since epoch, tells a reused PID apart from the old one
+    pub start_time: u64,
+    //Cumulative disk I/O in bytes
+    pub read_bytes: u64,
+    pub written_bytes: u64,
+}
+
+//Snapshot of every process known by sysinfo, busiest first
+//Threads are skipped, sysinfo lists them next to processes on Linux
+pub fn get_process_info(sys: &System) -> Vec<ProcessInfo> {
+    let mut processes: Vec<ProcessInfo> = sys
+        .processes()
+        .values()
+        .filter(|process| process.thread_kind().is_none())
+        .map(|process| {
+            let name = process.name().to_string_lossy().to_string();
+            let command = process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" ");
+            ProcessInfo {
+                pid: process.pid().as_u32(),
+                parent: process.parent().map(|pid| pid.as_u32()),
+                command: if command.is_empty() { format!("[{}]", name) } else { command },
+                name,
+                status: process.status(),
+                cpu_usage: process.cpu_usage(),
+                memory: process.memory(),
+                start_time: process.start_time(),
+                read_bytes: process.disk_usage().total_read_bytes,
+                written_bytes: process.disk_usage().total_written_bytes,
+            }
+        })
+        .collect();
+
+    processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage).then(a.pid.cmp(&b.pid)));
+    processes
+}
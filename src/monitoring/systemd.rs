@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+//Where systemd puts the service cgroups
+//Pure cgroup v2 hosts
+const UNIFIED_SLICE: &str = "/sys/fs/cgroup/system.slice";
+//cgroup v1 / hybrid hosts, one tree per controller
+const LEGACY_ROOT: &str = "/sys/fs/cgroup";
+
+//Share of a limit after which a unit gets flagged
+const NEAR_LIMIT_RATIO: f64 = 0.9;
+
+//Something about the unit that usually means it needs a restart
+pub enum UnitSignal {
+    //No process left in the cgroup, the service died or only forked away
+    Empty,
+    //Kernel OOM killer fired inside the unit
+    OomKilled(u64),
+    //memory close to MemoryMax=
+    MemoryNearLimit,
+    //tasks close to TasksMax=
+    TasksNearLimit,
+}
+
+impl UnitSignal {
+    pub fn label(&self) -> String {
+        match self {
+            UnitSignal::Empty => "empty cgroup".to_string(),
+            UnitSignal::OomKilled(count) => format!("oom-killed x{}", count),
+            UnitSignal::MemoryNearLimit => "memory near limit".to_string(),
+            UnitSignal::TasksNearLimit => "tasks near limit".to_string(),
+        }
+    }
+}
+
+//One systemd service, inferred from its cgroup
+pub struct UnitInfo {
+    //Unit name, ex: sshd.service
+    pub name: String,
+    //Percent of one core since the last tick
+    pub cpu_usage: f64,
+    //Bytes charged to the cgroup
+    pub memory: u64,
+    pub tasks: u64,
+    //Every process in the unit, sub-cgroups included
+    pub pids: Vec<u32>,
+    pub signals: Vec<UnitSignal>,
+}
+
+//Raw counters read from one cgroup
+struct UnitSample {
+    //Microseconds of CPU time
+    cpu_usec: Option<u64>,
+    memory: Option<u64>,
+    memory_max: Option<u64>,
+    tasks: Option<u64>,
+    tasks_max: Option<u64>,
+    oom_kills: u64,
+    pids: Vec<u32>,
+}
+
+//Cgroup hierarchy mounted on this host
+enum CgroupLayout {
+    Unified,
+    Legacy,
+    Missing,
+}
+
+pub struct UnitMonitor {
+    layout: CgroupLayout,
+    last_update: Instant,
+    //-> String == unit, u64 == CPU microseconds
+    last_cpu: HashMap<String, u64>,
+}
+
+impl UnitMonitor {
+    pub fn new() -> Self {
+        let layout = if Path::new(UNIFIED_SLICE).is_dir() {
+            CgroupLayout::Unified
+        } else if Path::new(LEGACY_ROOT).join("systemd/system.slice").is_dir() {
+            CgroupLayout::Legacy
+        } else {
+            CgroupLayout::Missing
+        };
+        UnitMonitor {
+            layout,
+            last_update: Instant::now(),
+            last_cpu: HashMap::new(),
+        }
+    }
+
+    //Return every *.service under system.slice with its usage
+    pub fn get_unit_info(&mut self) -> Vec<UnitInfo> {
+        let slice = match self.layout {
+            CgroupLayout::Unified => PathBuf::from(UNIFIED_SLICE),
+            CgroupLayout::Legacy => Path::new(LEGACY_ROOT).join("systemd/system.slice"),
+            CgroupLayout::Missing => return Vec::new(),
+        };
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+
+        let mut units = Vec::new();
+        let Ok(entries) = fs::read_dir(&slice) else {
+            return units;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.ends_with(".service") || !entry.path().is_dir() {
+                continue;
+            }
+            let sample = match self.layout {
+                CgroupLayout::Unified => unified_sample(&entry.path()),
+                _ => legacy_sample(&name),
+            };
+
+            //CPU counter is cumulative, rate needs the previous tick
+            let cpu_usage = match (sample.cpu_usec, self.last_cpu.get(&name)) {
+                (Some(current), Some(&last)) if elapsed > 0.0 => {
+                    current.saturating_sub(last) as f64 / (elapsed * 1_000_000.0) * 100.0
+                }
+                _ => 0.0,
+            };
+            if let Some(current) = sample.cpu_usec {
+                self.last_cpu.insert(name.clone(), current);
+            }
+
+            let memory = sample.memory.unwrap_or(0);
+            let tasks = sample.tasks.unwrap_or(sample.pids.len() as u64);
+            let mut signals = Vec::new();
+            if sample.pids.is_empty() {
+                signals.push(UnitSignal::Empty);
+            }
+            if sample.oom_kills > 0 {
+                signals.push(UnitSignal::OomKilled(sample.oom_kills));
+            }
+            if near_limit(memory, sample.memory_max) {
+                signals.push(UnitSignal::MemoryNearLimit);
+            }
+            if near_limit(tasks, sample.tasks_max) {
+                signals.push(UnitSignal::TasksNearLimit);
+            }
+
+            units.push(UnitInfo {
+                name,
+                cpu_usage,
+                memory,
+                tasks,
+                pids: sample.pids,
+                signals,
+            });
+        }
+
+        //Units that vanished don't need their counter anymore
+        self.last_cpu.retain(|name, _| units.iter().any(|unit| &unit.name == name));
+        self.last_update = now;
+        units.sort_by(|a, b| a.name.cmp(&b.name));
+        units
+    }
+}
+
+fn near_limit(value: u64, limit: Option<u64>) -> bool {
+    match limit {
+        Some(limit) if limit > 0 => value as f64 >= limit as f64 * NEAR_LIMIT_RATIO,
+        _ => false,
+    }
+}
+
+//cgroup v2: every controller lives in the same directory
+fn unified_sample(dir: &Path) -> UnitSample {
+    let cpu_usec = read_keyed(&dir.join("cpu.stat"), "usage_usec");
+    UnitSample {
+        cpu_usec,
+        memory: read_u64(&dir.join("memory.current")),
+        memory_max: read_u64(&dir.join("memory.max")),
+        tasks: read_u64(&dir.join("pids.current")),
+        tasks_max: read_u64(&dir.join("pids.max")),
+        oom_kills: read_keyed(&dir.join("memory.events"), "oom_kill").unwrap_or(0),
+        pids: read_procs(dir),
+    }
+}
+
+//cgroup v1: one hierarchy per controller, same relative path in each
+fn legacy_sample(unit: &str) -> UnitSample {
+    let dir = |controller: &str| Path::new(LEGACY_ROOT).join(controller).join("system.slice").join(unit);
+    let memory_dir = dir("memory");
+    let pids_dir = dir("pids");
+    UnitSample {
+        //cpuacct.usage is in nanoseconds
+        cpu_usec: read_u64(&dir("cpuacct").join("cpuacct.usage")).map(|ns| ns / 1_000),
+        memory: read_u64(&memory_dir.join("memory.usage_in_bytes")),
+        //v1 reports "no limit" as a huge page-aligned number
+        memory_max: read_u64(&memory_dir.join("memory.limit_in_bytes")).filter(|&max| max < i64::MAX as u64 / 2),
+        tasks: read_u64(&pids_dir.join("pids.current")),
+        tasks_max: read_u64(&pids_dir.join("pids.max")),
+        oom_kills: read_keyed(&memory_dir.join("memory.oom_control"), "oom_kill").unwrap_or(0),
+        pids: read_procs(&dir("systemd")),
+    }
+}
+
+//Single number file, "max" reads as None
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+//"key value" lines, ex: cpu.stat, memory.events
+fn read_keyed(path: &Path, key: &str) -> Option<u64> {
+    fs::read_to_string(path)
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.trim().parse().ok())
+}
+
+//cgroup.procs of the unit and of every sub-cgroup below it
+fn read_procs(dir: &Path) -> Vec<u32> {
+    let mut pids: Vec<u32> = fs::read_to_string(dir.join("cgroup.procs"))
+        .map(|content| content.lines().filter_map(|line| line.trim().parse().ok()).collect())
+        .unwrap_or_default();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                pids.extend(read_procs(&entry.path()));
+            }
+        }
+    }
+    pids
+}
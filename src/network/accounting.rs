@@ -0,0 +1,431 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use nix::libc;
+
+use super::network::NetworkData;
+use super::utils::InterfaceKind;
+
+//Write to disk at most this often, and when dropped
+const SAVE_EVERY: Duration = Duration::from_secs(60);
+//Buckets kept per granularity
+const KEEP_HOURS: usize = 48;
+const KEEP_DAYS: usize = 62;
+const KEEP_MONTHS: usize = 36;
+//Share of the monthly quota colored as warning / alert
+pub const QUOTA_WARN_RATIO: f64 = 0.8;
+pub const QUOTA_ALERT_RATIO: f64 = 1.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Period {
+    Hour,
+    Day,
+    Month,
+}
+
+impl Period {
+    fn tag(&self) -> &'static str {
+        match self {
+            Period::Hour => "h",
+            Period::Day => "d",
+            Period::Month => "m",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "h" => Some(Period::Hour),
+            "d" => Some(Period::Day),
+            "m" => Some(Period::Month),
+            _ => None,
+        }
+    }
+}
+
+//Bytes received / transmitted in one bucket
+#[derive(Clone, Copy, Default)]
+pub struct Usage {
+    pub rx: u64,
+    pub tx: u64,
+}
+
+impl Usage {
+    pub fn total(&self) -> u64 {
+        self.rx + self.tx
+    }
+}
+
+//vnstat-like byte accounting per interface, by local hour, day and month
+//Stored as tab separated lines:
+//  boot  <boot_id>
+//  last  <interface> <rx counter> <tx counter>
+//  h|d|m <interface> <2026-10-19T14 | 2026-10-19 | 2026-10> <rx> <tx>
+pub struct Accounting {
+    path: Option<PathBuf>,
+    //-> (interface, period) == bucket key, usage; keys sort chronologically
+    buckets: HashMap<(String, Period), BTreeMap<String, Usage>>,
+    //-> String == Interface, last raw counters (rx, tx)
+    last_counters: HashMap<String, (u64, u64)>,
+    boot_id: String,
+    last_save: Instant,
+    dirty: bool,
+    //No store yet: the first counters seen are a baseline, not traffic to account
+    first_run: bool,
+}
+
+impl Accounting {
+    //None path == in memory only
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let boot_id = fs::read_to_string("/proc/sys/kernel/random/boot_id")
+            .map(|id| id.trim().to_string())
+            .unwrap_or_default();
+        let mut accounting = Accounting {
+            path,
+            buckets: HashMap::new(),
+            last_counters: HashMap::new(),
+            boot_id,
+            last_save: Instant::now(),
+            dirty: false,
+            first_run: false,
+        };
+        let content = accounting.path.as_ref().and_then(|path| fs::read_to_string(path).ok());
+        accounting.first_run = content.is_none();
+        let content = content.unwrap_or_default();
+
+        let mut same_boot = false;
+        for line in content.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields[..] {
+                ["boot", id] => same_boot = !id.is_empty() && id == accounting.boot_id,
+                ["last", interface, rx, tx] => {
+                    if let (Ok(rx), Ok(tx)) = (rx.parse(), tx.parse()) {
+                        accounting.last_counters.insert(interface.to_string(), (rx, tx));
+                    }
+                }
+                [tag, interface, key, rx, tx] => {
+                    let (Some(period), Ok(rx), Ok(tx)) = (Period::from_tag(tag), rx.parse(), tx.parse()) else {
+                        continue;
+                    };
+                    accounting
+                        .buckets
+                        .entry((interface.to_string(), period))
+                        .or_default()
+                        .insert(key.to_string(), Usage { rx, tx });
+                }
+                _ => {}
+            }
+        }
+        //Rebooted since: the counters started again from 0, everything they hold is new traffic
+        if !same_boot {
+            accounting.last_counters.clear();
+        }
+        accounting
+    }
+
+    pub fn record(&mut self, networks: &[NetworkData]) {
+        let (hour, day, month) = bucket_keys(SystemTime::now());
+        for network in networks {
+            //Bridges carry what their ports already counted
+            if network.hidden || matches!(network.kind, InterfaceKind::Loopback | InterfaceKind::Bridge) {
+                continue;
+            }
+            let current = (network.total_received, network.total_transmitted);
+            let last = self.last_counters.get(&network.interface).copied();
+            if last.is_none() && self.first_run {
+                self.last_counters.insert(network.interface.clone(), current);
+                self.dirty = true;
+                continue;
+            }
+            //Unknown interface: created since the last run, all its traffic is new
+            let (last_rx, last_tx) = last.unwrap_or((0, 0));
+            //A counter going backwards was reset (driver reload, interface recreated): count from 0
+            let delta = |current: u64, last: u64| if current >= last { current - last } else { current };
+            let usage = Usage {
+                rx: delta(current.0, last_rx),
+                tx: delta(current.1, last_tx),
+            };
+            self.last_counters.insert(network.interface.clone(), current);
+            if usage.total() == 0 {
+                continue;
+            }
+
+            for (period, key) in [(Period::Hour, &hour), (Period::Day, &day), (Period::Month, &month)] {
+                let buckets = self.buckets.entry((network.interface.clone(), period)).or_default();
+                let bucket = buckets.entry(key.clone()).or_default();
+                bucket.rx += usage.rx;
+                bucket.tx += usage.tx;
+                let keep = match period {
+                    Period::Hour => KEEP_HOURS,
+                    Period::Day => KEEP_DAYS,
+                    Period::Month => KEEP_MONTHS,
+                };
+                while buckets.len() > keep {
+                    buckets.pop_first();
+                }
+            }
+            self.dirty = true;
+        }
+        //Removed interfaces, one coming back under the same name starts from a clean baseline
+        //Their buckets stay: ppp0, wwan0 or a tunnel only exist while connected and still count towards the quota
+        let counters = self.last_counters.len();
+        self.last_counters
+            .retain(|interface, _| networks.iter().any(|network| &network.interface == interface));
+        if counters != self.last_counters.len() {
+            self.dirty = true;
+        }
+        //Interfaces showing up from now on are new, their traffic counts
+        self.first_run = false;
+
+        if self.last_save.elapsed() >= SAVE_EVERY {
+            self.save();
+        }
+    }
+
+    //Last `count` buckets of an interface, oldest first, empty buckets included
+    pub fn history(&self, interface: &str, period: Period, count: usize) -> Vec<(String, Usage)> {
+        let now = SystemTime::now();
+        let step = match period {
+            Period::Hour => 3_600,
+            Period::Day => 86_400,
+            Period::Month => 0,
+        };
+        let buckets = self.buckets.get(&(interface.to_string(), period));
+        let mut keys: Vec<String> = if period == Period::Month {
+            buckets.map(|buckets| buckets.keys().cloned().collect()).unwrap_or_default()
+        } else {
+            (0..count as u64)
+                .rev()
+                .map(|ago| {
+                    let (hour, day, _) = bucket_keys(now - Duration::from_secs(ago * step));
+                    if period == Period::Hour { hour } else { day }
+                })
+                .collect()
+        };
+        let skip = keys.len().saturating_sub(count);
+        keys.drain(..skip);
+        keys.into_iter()
+            .map(|key| {
+                let usage = buckets.and_then(|buckets| buckets.get(&key)).copied().unwrap_or_default();
+                (key, usage)
+            })
+            .collect()
+    }
+
+    //Usage of the current hour, day or month
+    pub fn current(&self, interface: &str, period: Period) -> Usage {
+        let (hour, day, month) = bucket_keys(SystemTime::now());
+        let key = match period {
+            Period::Hour => hour,
+            Period::Day => day,
+            Period::Month => month,
+        };
+        self.buckets
+            .get(&(interface.to_string(), period))
+            .and_then(|buckets| buckets.get(&key))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn save(&mut self) {
+        self.last_save = Instant::now();
+        let Some(path) = &self.path else {
+            return;
+        };
+        if !self.dirty {
+            return;
+        }
+        let mut content = format!("boot\t{}\n", self.boot_id);
+        for (interface, (rx, tx)) in &self.last_counters {
+            content.push_str(&format!("last\t{}\t{}\t{}\n", interface, rx, tx));
+        }
+        let mut keys: Vec<_> = self.buckets.keys().collect();
+        keys.sort();
+        for key in keys {
+            let (interface, period) = key;
+            for (bucket, usage) in &self.buckets[key] {
+                content.push_str(&format!("{}\t{}\t{}\t{}\t{}\n", period.tag(), interface, bucket, usage.rx, usage.tx));
+            }
+        }
+        //Write then rename, a crash never leaves half a file
+        let temporary = path.with_extension("tmp");
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&temporary, content))
+            .and_then(|_| fs::rename(&temporary, path));
+        if written.is_ok() {
+            self.dirty = false;
+        }
+    }
+}
+
+impl Drop for Accounting {
+    //Exiting keeps what was counted since the last save
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+//Local time (hour, day, month) keys: 2026-10-19T14, 2026-10-19, 2026-10
+fn bucket_keys(at: SystemTime) -> (String, String, String) {
+    let secs = at.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs() as i64);
+    keys_from_seconds(secs + utc_offset(secs))
+}
+
+//Seconds east of UTC at that instant, daylight saving included, 0 when the timezone can't be resolved
+fn utc_offset(secs: i64) -> i64 {
+    let time = secs as libc::time_t;
+    //Safety: all zeroes is a valid struct tm, tm_zone is only read when set by localtime_r
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    //Safety: both pointers are valid for the call, localtime_r doesn't keep them
+    let result = unsafe { libc::localtime_r(&time, &mut tm) };
+    if result.is_null() {
+        0
+    } else {
+        tm.tm_gmtoff as i64
+    }
+}
+
+//Keys of a wall clock time given as seconds since 1970-01-01T00
+fn keys_from_seconds(secs: i64) -> (String, String, String) {
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let hour = secs.rem_euclid(86_400) / 3_600;
+    (
+        format!("{:04}-{:02}-{:02}T{:02}", year, month, day, hour),
+        format!("{:04}-{:02}-{:02}", year, month, day),
+        format!("{:04}-{:02}", year, month),
+    )
+}
+
+//Days since 1970-01-01 to (year, month, day), proleptic Gregorian
+//http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_from_days_known_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(10_956), (1999, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(20_745), (2026, 10, 19));
+    }
+
+    #[test]
+    fn keys_at_epoch() {
+        let (hour, day, month) = keys_from_seconds(0);
+        assert_eq!((hour.as_str(), day.as_str(), month.as_str()), ("1970-01-01T00", "1970-01-01", "1970-01"));
+    }
+
+    #[test]
+    fn keys_around_leap_day() {
+        let leap_day = 11_016 * 86_400;
+        let (hour, day, month) = keys_from_seconds(leap_day + 23 * 3_600 + 3_599);
+        assert_eq!((hour.as_str(), day.as_str(), month.as_str()), ("2000-02-29T23", "2000-02-29", "2000-02"));
+        let (hour, day, month) = keys_from_seconds(leap_day + 24 * 3_600);
+        assert_eq!((hour.as_str(), day.as_str(), month.as_str()), ("2000-03-01T00", "2000-03-01", "2000-03"));
+    }
+
+    #[test]
+    fn keys_around_year_end() {
+        let new_year = 10_957 * 86_400;
+        let (hour, day, month) = keys_from_seconds(new_year - 1);
+        assert_eq!((hour.as_str(), day.as_str(), month.as_str()), ("1999-12-31T23", "1999-12-31", "1999-12"));
+        let (hour, day, month) = keys_from_seconds(new_year);
+        assert_eq!((hour.as_str(), day.as_str(), month.as_str()), ("2000-01-01T00", "2000-01-01", "2000-01"));
+    }
+
+    fn network(interface: &str, received: u64, transmitted: u64) -> NetworkData {
+        NetworkData {
+            interface: interface.to_string(),
+            mac_address: String::new(),
+            kind: InterfaceKind::Physical,
+            hidden: false,
+            addresses: Vec::new(),
+            total_received: received,
+            total_transmitted: transmitted,
+            rx_rate: 0.0,
+            tx_rate: 0.0,
+            rx_peak: 0.0,
+            tx_peak: 0.0,
+            packets: Default::default(),
+            link: Default::default(),
+            best_speed: None,
+        }
+    }
+
+    #[test]
+    fn disconnected_interface_keeps_its_usage() {
+        let mut accounting = Accounting::load(None);
+        //Baseline, then 1500 bytes on the modem
+        accounting.record(&[network("eth0", 0, 0), network("ppp0", 0, 0)]);
+        accounting.record(&[network("eth0", 0, 0), network("ppp0", 1_000, 500)]);
+        assert_eq!(accounting.current("ppp0", Period::Month).total(), 1_500);
+
+        //Modem unplugged
+        accounting.record(&[network("eth0", 10, 10)]);
+        assert_eq!(accounting.current("ppp0", Period::Month).total(), 1_500);
+        assert!(!accounting.last_counters.contains_key("ppp0"));
+
+        //Plugged back in, counters start from 0 again
+        accounting.record(&[network("eth0", 10, 10), network("ppp0", 200, 0)]);
+        assert_eq!(accounting.current("ppp0", Period::Month).total(), 1_700);
+    }
+
+    #[test]
+    fn load_reads_back_what_save_wrote() {
+        let path = std::env::temp_dir().join(format!("rusys-accounting-test-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut accounting = Accounting::load(Some(path.clone()));
+        assert!(accounting.first_run);
+        accounting.last_counters.insert("eth0".to_string(), (1_000, 2_000));
+        for (period, key, rx, tx) in [
+            (Period::Hour, "2026-10-19T14", 10, 20),
+            (Period::Day, "2026-10-19", 30, 40),
+            (Period::Month, "2026-09", 50, 60),
+            (Period::Month, "2026-10", 70, 80),
+        ] {
+            accounting
+                .buckets
+                .entry(("eth0".to_string(), period))
+                .or_default()
+                .insert(key.to_string(), Usage { rx, tx });
+        }
+        accounting.dirty = true;
+        accounting.save();
+        assert!(!accounting.dirty);
+        drop(accounting);
+
+        let loaded = Accounting::load(Some(path.clone()));
+        let _ = fs::remove_file(&path);
+        assert!(!loaded.first_run);
+        //Same boot: the raw counters are still the baseline
+        assert_eq!(loaded.last_counters.get("eth0"), Some(&(1_000, 2_000)));
+        let usage = |period: Period, key: &str| {
+            loaded.buckets[&("eth0".to_string(), period)].get(key).map(|usage| (usage.rx, usage.tx))
+        };
+        assert_eq!(usage(Period::Hour, "2026-10-19T14"), Some((10, 20)));
+        assert_eq!(usage(Period::Day, "2026-10-19"), Some((30, 40)));
+        assert_eq!(usage(Period::Month, "2026-09"), Some((50, 60)));
+        assert_eq!(usage(Period::Month, "2026-10"), Some((70, 80)));
+        assert_eq!(loaded.buckets[&("eth0".to_string(), Period::Month)].len(), 2);
+    }
+}
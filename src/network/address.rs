@@ -0,0 +1,62 @@
+use std::net::IpAddr;
+use sysinfo::IpNetwork;
+
+//Reach of an address, from its range. Declared in display order
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AddressScope {
+    Global,
+    //fc00::/7, IPv6 private range
+    UniqueLocal,
+    //fe80::/10, 169.254.0.0/16
+    LinkLocal,
+    //::1, 127.0.0.0/8
+    Host,
+}
+
+impl AddressScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AddressScope::Global => "global",
+            AddressScope::LinkLocal => "link",
+            AddressScope::UniqueLocal => "ula",
+            AddressScope::Host => "host",
+        }
+    }
+}
+
+//One address assigned to an interface
+#[derive(Clone)]
+pub struct InterfaceAddress {
+    pub address: IpAddr,
+    pub prefix: u8,
+    pub scope: AddressScope,
+}
+
+impl InterfaceAddress {
+    pub fn from_network(network: &IpNetwork) -> Self {
+        InterfaceAddress {
+            address: network.addr,
+            prefix: network.prefix,
+            scope: scope_of(&network.addr),
+        }
+    }
+
+    pub fn family(&self) -> &'static str {
+        match self.address {
+            IpAddr::V4(_) => "IPv4",
+            IpAddr::V6(_) => "IPv6",
+        }
+    }
+}
+
+fn scope_of(address: &IpAddr) -> AddressScope {
+    match address {
+        IpAddr::V4(v4) if v4.is_loopback() => AddressScope::Host,
+        IpAddr::V4(v4) if v4.is_link_local() => AddressScope::LinkLocal,
+        IpAddr::V4(_) => AddressScope::Global,
+        IpAddr::V6(v6) if v6.is_loopback() => AddressScope::Host,
+        IpAddr::V6(v6) if v6.segments()[0] & 0xffc0 == 0xfe80 => AddressScope::LinkLocal,
+        IpAddr::V6(v6) if v6.segments()[0] & 0xfe00 == 0xfc00 => AddressScope::UniqueLocal,
+        IpAddr::V6(_) => AddressScope::Global,
+    }
+}
@@ -0,0 +1,95 @@
+use std::fs;
+use std::path::Path;
+
+//Per second rates from /sys/class/net/<if>/statistics
+#[derive(Clone, Copy, Default)]
+pub struct PacketRates {
+    pub rx_packets: f64,
+    pub tx_packets: f64,
+    pub rx_errors: f64,
+    pub tx_errors: f64,
+    pub rx_dropped: f64,
+    pub tx_dropped: f64,
+    //rx_fifo_errors + tx_fifo_errors, ring buffer overruns
+    pub fifo: f64,
+    pub collisions: f64,
+    //Multicast packets received
+    pub multicast: f64,
+}
+
+impl PacketRates {
+    //Anything going wrong on the link right now
+    pub fn has_faults(&self) -> bool {
+        self.rx_errors + self.tx_errors + self.rx_dropped + self.tx_dropped + self.fifo + self.collisions > 0.0
+    }
+}
+
+//Cumulative counters, read once per tick
+#[derive(Clone, Copy)]
+pub struct InterfaceCounters {
+    rx_packets: u64,
+    tx_packets: u64,
+    rx_errors: u64,
+    tx_errors: u64,
+    rx_dropped: u64,
+    tx_dropped: u64,
+    fifo: u64,
+    collisions: u64,
+    multicast: u64,
+}
+
+impl InterfaceCounters {
+    //None when the interface has no statistics directory (gone meanwhile)
+    pub fn read(interface: &str) -> Option<Self> {
+        let dir = Path::new("/sys/class/net").join(interface).join("statistics");
+        if !dir.is_dir() {
+            return None;
+        }
+        //Missing counter files read as 0, some drivers don't implement all of them
+        let read = |file: &str| {
+            fs::read_to_string(dir.join(file))
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .unwrap_or(0)
+        };
+        Some(InterfaceCounters {
+            rx_packets: read("rx_packets"),
+            tx_packets: read("tx_packets"),
+            rx_errors: read("rx_errors"),
+            tx_errors: read("tx_errors"),
+            rx_dropped: read("rx_dropped"),
+            tx_dropped: read("tx_dropped"),
+            fifo: read("rx_fifo_errors") + read("tx_fifo_errors"),
+            collisions: read("collisions"),
+            multicast: read("multicast"),
+        })
+    }
+
+    //A counter lower than last time was reset, its delta can't be trusted
+    pub fn is_reset(&self, last: &InterfaceCounters) -> bool {
+        self.rx_packets < last.rx_packets
+            || self.tx_packets < last.tx_packets
+            || self.rx_errors < last.rx_errors
+            || self.tx_errors < last.tx_errors
+            || self.rx_dropped < last.rx_dropped
+            || self.tx_dropped < last.tx_dropped
+            || self.fifo < last.fifo
+            || self.collisions < last.collisions
+            || self.multicast < last.multicast
+    }
+
+    pub fn rates(&self, last: &InterfaceCounters, elapsed: f64) -> PacketRates {
+        let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / elapsed;
+        PacketRates {
+            rx_packets: rate(self.rx_packets, last.rx_packets),
+            tx_packets: rate(self.tx_packets, last.tx_packets),
+            rx_errors: rate(self.rx_errors, last.rx_errors),
+            tx_errors: rate(self.tx_errors, last.tx_errors),
+            rx_dropped: rate(self.rx_dropped, last.rx_dropped),
+            tx_dropped: rate(self.tx_dropped, last.tx_dropped),
+            fifo: rate(self.fifo, last.fifo),
+            collisions: rate(self.collisions, last.collisions),
+            multicast: rate(self.multicast, last.multicast),
+        }
+    }
+}
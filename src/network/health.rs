@@ -0,0 +1,111 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::time::Instant;
+
+//(section, counter, label) tracked from /proc/net/snmp and /proc/net/netstat
+const COUNTERS: [(&str, &str, &str); 9] = [
+    ("Tcp", "RetransSegs", "Retransmitted segments"),
+    ("Tcp", "OutRsts", "RSTs sent"),
+    //Established connections reset by the peer, the closest to RSTs received
+    ("Tcp", "EstabResets", "RSTs received (estab)"),
+    ("TcpExt", "ListenOverflows", "Listen queue overflows"),
+    ("TcpExt", "ListenDrops", "Listen drops"),
+    ("TcpExt", "SyncookiesSent", "SYN cookies sent"),
+    ("TcpExt", "SyncookiesFailed", "SYN cookies failed"),
+    ("Udp", "RcvbufErrors", "UDP receive buffer errors"),
+    ("Udp", "InErrors", "UDP input errors"),
+];
+
+///Rate of one protocol counter
+pub struct ProtocolRate {
+    //Counter name from the kernel, ex: RetransSegs
+    pub name: &'static str,
+    pub label: &'static str,
+    //Per second since the last update
+    pub rate: f64,
+    //Since boot
+    pub total: u64,
+}
+
+//TCP/UDP health counters from /proc/net/snmp and /proc/net/netstat
+pub struct ProtocolMonitor {
+    //Last update; collect metrics
+    last_update: Instant,
+    //-> &str == counter name, u64 == cumulative value
+    last_counters: HashMap<&'static str, u64>,
+    //-> &str == counter name, rates oldest first
+    history: HashMap<&'static str, VecDeque<f64>>,
+    //Samples kept per counter
+    history_length: usize,
+}
+
+impl ProtocolMonitor {
+    pub fn new(history_length: usize) -> Self {
+        ProtocolMonitor {
+            last_update: Instant::now(),
+            last_counters: HashMap::new(),
+            history: HashMap::new(),
+            history_length,
+        }
+    }
+
+    //Rates of a counter, oldest first
+    pub fn history(&self, name: &str) -> impl DoubleEndedIterator<Item = &f64> {
+        self.history.get(name).into_iter().flatten()
+    }
+
+    //None when called again too soon to compute rates
+    pub fn get_protocol_health(&mut self) -> Option<Vec<ProtocolRate>> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+
+        if elapsed < 0.1 {
+            return None;
+        }
+        let contents = ["/proc/net/snmp", "/proc/net/netstat"].map(|file| fs::read_to_string(file).unwrap_or_default());
+        let mut values = HashMap::new();
+        for content in &contents {
+            parse_counters(content, &mut values);
+        }
+
+        let mut rates = Vec::new();
+        for (section, name, label) in COUNTERS {
+            //Older kernels lack some counters
+            let Some(&total) = values.get(&(section, name)) else {
+                continue;
+            };
+            let rate = match self.last_counters.get(name) {
+                Some(&last) => total.saturating_sub(last) as f64 / elapsed,
+                None => 0.0,
+            };
+            let history = self.history.entry(name).or_default();
+            history.push_back(rate);
+            while history.len() > self.history_length {
+                history.pop_front();
+            }
+            self.last_counters.insert(name, total);
+            rates.push(ProtocolRate { name, label, rate, total });
+        }
+        //Updated
+        self.last_update = now;
+        Some(rates)
+    }
+}
+
+//Header line then value line per section:
+//Tcp: RtoAlgorithm RtoMin ... RetransSegs ...
+//Tcp: 1 200 ... 12 ...
+fn parse_counters<'a>(content: &'a str, values: &mut HashMap<(&'a str, &'a str), u64>) {
+    let mut lines = content.lines();
+    while let (Some(header), Some(data)) = (lines.next(), lines.next()) {
+        let (Some((section, names)), Some((_, numbers))) = (header.split_once(':'), data.split_once(':')) else {
+            continue;
+        };
+        for (name, number) in names.split_whitespace().zip(numbers.split_whitespace()) {
+            //Negative ones are constants like MaxConn -1, not counters
+            if let Ok(number) = number.parse() {
+                values.insert((section, name), number);
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::fs;
+use std::path::Path;
+
+//Link layer state from /sys/class/net/<if>
+#[derive(Clone, Default)]
+pub struct LinkInfo {
+    //up, down, dormant, lowerlayerdown, unknown...
+    pub operstate: String,
+    //Negotiated speed in Mb/s, None when down or not reported (virtual interfaces)
+    pub speed: Option<u64>,
+    //full / half, None when not reported
+    pub duplex: Option<String>,
+    pub mtu: u64,
+    //Carrier up/down transitions since the interface was created
+    pub carrier_changes: u64,
+}
+
+impl LinkInfo {
+    pub fn read(interface: &str) -> Self {
+        let dir = Path::new("/sys/class/net").join(interface);
+        //speed and duplex fail with EINVAL when the link is down
+        let read = |file: &str| fs::read_to_string(dir.join(file)).ok().map(|value| value.trim().to_string());
+
+        LinkInfo {
+            operstate: read("operstate").unwrap_or_else(|| "unknown".to_string()),
+            //-1, and SPEED_UNKNOWN read back as u16 or u32 by some drivers
+            speed: read("speed")
+                .and_then(|speed| speed.parse::<i64>().ok())
+                .filter(|speed| *speed > 0 && *speed != 65535 && *speed != 4294967295)
+                .map(|speed| speed as u64),
+            duplex: read("duplex").filter(|duplex| duplex != "unknown"),
+            mtu: read("mtu").and_then(|mtu| mtu.parse().ok()).unwrap_or(0),
+            carrier_changes: read("carrier_changes").and_then(|changes| changes.parse().ok()).unwrap_or(0),
+        }
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.operstate == "up"
+    }
+
+    //Bytes per second as a percentage of the link speed
+    pub fn utilization(&self, rate: f64) -> Option<f64> {
+        self.speed.map(|speed| rate * 8.0 / (speed as f64 * 1_000_000.0) * 100.0)
+    }
+}
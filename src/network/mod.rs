@@ -1,3 +1,20 @@
+mod accounting;
+pub use self::accounting::{Accounting, Period, QUOTA_ALERT_RATIO, QUOTA_WARN_RATIO};
+mod address;
+pub use self::address::AddressScope;
+mod counters;
+mod health;
+pub use self::health::{ProtocolMonitor, ProtocolRate};
+mod link;
+mod netlink;
+#[allow(clippy::module_inception)]
 mod network;
-pub use self::network::{NetworkData, NetworkMonitor};
+pub use self::network::{InterfaceEventKind, NetworkData, NetworkMonitor};
+mod routes;
+pub use self::routes::{get_dns_config, get_neighbors, get_routes, DnsConfig, Neighbor, Route};
+mod sockets;
+pub use self::sockets::{Endpoint, SocketInfo, SocketMonitor};
 mod utils;
+pub use self::utils::InterfaceKind;
+mod wireless;
+pub use self::wireless::{WirelessData, WirelessMonitor};
@@ -0,0 +1,197 @@
+use std::os::fd::AsRawFd;
+
+use nix::sys::socket::{
+    bind, recv, send, setsockopt, socket, sockopt, AddressFamily, MsgFlags, NetlinkAddr, SockFlag, SockProtocol,
+    SockType,
+};
+use nix::sys::time::TimeVal;
+
+//From include/uapi/linux/netlink.h
+pub const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_MULTI: u16 = 0x2;
+pub const NLM_F_DUMP: u16 = 0x300;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+//struct nlmsghdr
+const HEADER_LEN: usize = 16;
+//Large enough for the biggest datagram a dump sends
+const BUFFER_LEN: usize = 64 * 1024;
+
+//One answer of the kernel: message type and payload after the nlmsghdr
+pub struct Message {
+    pub kind: u16,
+    pub payload: Vec<u8>,
+}
+
+//Send one request and collect every answer, following multipart dumps until NLMSG_DONE
+//None when netlink isn't reachable, the kernel answers with an error or doesn't answer at all
+pub fn request(protocol: SockProtocol, kind: u16, flags: u16, payload: &[u8]) -> Option<Vec<Message>> {
+    let fd = socket(AddressFamily::Netlink, SockType::Raw, SockFlag::SOCK_CLOEXEC, protocol).ok()?;
+    bind(fd.as_raw_fd(), &NetlinkAddr::new(0, 0)).ok()?;
+    //Runs on the UI thread, never wait for long
+    setsockopt(&fd, sockopt::ReceiveTimeout, &TimeVal::new(0, 200_000)).ok()?;
+
+    //nlmsghdr {len, type, flags, seq, pid}
+    let mut message = Vec::with_capacity(HEADER_LEN + payload.len());
+    message.extend_from_slice(&((HEADER_LEN + payload.len()) as u32).to_ne_bytes());
+    message.extend_from_slice(&kind.to_ne_bytes());
+    message.extend_from_slice(&flags.to_ne_bytes());
+    message.extend_from_slice(&1u32.to_ne_bytes());
+    message.extend_from_slice(&0u32.to_ne_bytes());
+    message.extend_from_slice(payload);
+    send(fd.as_raw_fd(), &message, MsgFlags::empty()).ok()?;
+
+    let mut messages = Vec::new();
+    let mut buffer = vec![0u8; BUFFER_LEN];
+    loop {
+        let length = recv(fd.as_raw_fd(), &mut buffer, MsgFlags::empty()).ok()?;
+        if parse_datagram(buffer.get(..length)?, &mut messages)? {
+            return Some(messages);
+        }
+    }
+}
+
+//Append the messages of one datagram, Some(true) once the answer is complete
+fn parse_datagram(mut datagram: &[u8], messages: &mut Vec<Message>) -> Option<bool> {
+    let mut done = false;
+    while datagram.len() >= HEADER_LEN {
+        let length = u32::from_ne_bytes(datagram[..4].try_into().ok()?) as usize;
+        let kind = u16::from_ne_bytes([datagram[4], datagram[5]]);
+        let flags = u16::from_ne_bytes([datagram[6], datagram[7]]);
+        //Truncated or corrupt, nothing after it can be trusted
+        if length < HEADER_LEN || length > datagram.len() {
+            return None;
+        }
+        let payload = &datagram[HEADER_LEN..length];
+        match kind {
+            NLMSG_DONE => return Some(true),
+            //errno as a negative int, 0 == acknowledgement
+            NLMSG_ERROR => {
+                let error = i32::from_ne_bytes(payload.get(..4)?.try_into().ok()?);
+                return if error == 0 { Some(true) } else { None };
+            }
+            _ => messages.push(Message {
+                kind,
+                payload: payload.to_vec(),
+            }),
+        }
+        //Single answers are complete in one datagram, dumps end with NLMSG_DONE
+        done = flags & NLM_F_MULTI == 0;
+        datagram = datagram.get(align(length)..).unwrap_or_default();
+    }
+    Some(done)
+}
+
+//Payload of the first rtattr / nlattr of that type, attributes are 4-byte aligned
+pub fn attribute(mut attributes: &[u8], wanted: u16) -> Option<&[u8]> {
+    while attributes.len() >= 4 {
+        let length = u16::from_ne_bytes([attributes[0], attributes[1]]) as usize;
+        //Top bits are the nested / byte order flags
+        let kind = u16::from_ne_bytes([attributes[2], attributes[3]]) & 0x3fff;
+        if length < 4 || length > attributes.len() {
+            return None;
+        }
+        if kind == wanted {
+            return Some(&attributes[4..length]);
+        }
+        attributes = attributes.get(align(length)..).unwrap_or_default();
+    }
+    None
+}
+
+//Append an attribute, padded to the next 4 bytes
+pub fn push_attribute(buffer: &mut Vec<u8>, kind: u16, value: &[u8]) {
+    buffer.extend_from_slice(&((4 + value.len()) as u16).to_ne_bytes());
+    buffer.extend_from_slice(&kind.to_ne_bytes());
+    buffer.extend_from_slice(value);
+    buffer.resize(align(buffer.len()), 0);
+}
+
+fn align(length: usize) -> usize {
+    (length + 3) & !3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(kind: u16, flags: u16, payload: &[u8]) -> Vec<u8> {
+        let mut message = Vec::new();
+        message.extend_from_slice(&((HEADER_LEN + payload.len()) as u32).to_ne_bytes());
+        message.extend_from_slice(&kind.to_ne_bytes());
+        message.extend_from_slice(&flags.to_ne_bytes());
+        message.extend_from_slice(&[0; 8]);
+        message.extend_from_slice(payload);
+        message
+    }
+
+    #[test]
+    fn nested_attribute() {
+        //IFLA_LINKINFO (18) holding IFLA_INFO_KIND (1) "veth\0"
+        let mut link_info = Vec::new();
+        push_attribute(&mut link_info, 1, b"veth\0");
+        let mut attributes = Vec::new();
+        push_attribute(&mut attributes, 3, b"veth0\0");
+        push_attribute(&mut attributes, 18 | 0x8000, &link_info);
+
+        let nested = attribute(&attributes, 18).unwrap();
+        assert_eq!(attribute(nested, 1), Some(&b"veth\0"[..]));
+        assert_eq!(attribute(&attributes, 4), None);
+    }
+
+    #[test]
+    fn attributes_are_padded() {
+        let mut attributes = Vec::new();
+        //5 byte payload, 3 bytes of padding before the next one
+        push_attribute(&mut attributes, 1, b"abcde");
+        push_attribute(&mut attributes, 2, &7u32.to_ne_bytes());
+        assert_eq!(attributes.len(), 12 + 8);
+        assert_eq!(&attributes[9..12], &[0, 0, 0]);
+        assert_eq!(attribute(&attributes, 2), Some(&7u32.to_ne_bytes()[..]));
+    }
+
+    #[test]
+    fn truncated_attribute() {
+        let mut attributes = Vec::new();
+        push_attribute(&mut attributes, 1, b"abcdefgh");
+        attributes.truncate(8);
+        assert_eq!(attribute(&attributes, 1), None);
+    }
+
+    #[test]
+    fn attribute_shorter_than_its_header() {
+        //length 2 would loop forever if it was trusted
+        let attributes = [&2u16.to_ne_bytes()[..], &1u16.to_ne_bytes()[..], &[0; 4]].concat();
+        assert_eq!(attribute(&attributes, 1), None);
+    }
+
+    #[test]
+    fn single_answer_is_complete() {
+        let mut messages = Vec::new();
+        assert_eq!(parse_datagram(&message(16, 0, &[1, 2, 3, 4]), &mut messages), Some(true));
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].payload, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn dump_runs_until_done() {
+        let mut messages = Vec::new();
+        let first = [message(16, NLM_F_MULTI, &[1; 4]), message(16, NLM_F_MULTI, &[2; 4])].concat();
+        assert_eq!(parse_datagram(&first, &mut messages), Some(false));
+        let last = [message(16, NLM_F_MULTI, &[3; 4]), message(NLMSG_DONE, NLM_F_MULTI, &[0; 4])].concat();
+        assert_eq!(parse_datagram(&last, &mut messages), Some(true));
+        assert_eq!(messages.len(), 3);
+    }
+
+    #[test]
+    fn error_and_acknowledgement() {
+        let mut messages = Vec::new();
+        //-ENODEV
+        assert_eq!(parse_datagram(&message(NLMSG_ERROR, 0, &(-19i32).to_ne_bytes()), &mut messages), None);
+        assert_eq!(parse_datagram(&message(NLMSG_ERROR, 0, &0i32.to_ne_bytes()), &mut messages), Some(true));
+        //Length past the end of the datagram
+        let mut truncated = message(16, 0, &[0; 8]);
+        truncated.truncate(20);
+        assert_eq!(parse_datagram(&truncated, &mut messages), None);
+    }
+}
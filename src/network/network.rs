@@ -1,4 +1,10 @@
-use super::utils::is_phy;
+use super::address::InterfaceAddress;
+use super::counters::{InterfaceCounters, PacketRates};
+use super::link::LinkInfo;
+use super::utils::{interface_index, interface_kind, is_selected, InterfaceKind};
+use crate::config::Config;
+use crate::smoothing::{Smoother, Smoothing};
+use std::collections::VecDeque;
 use std::time::Instant;
 use sysinfo::{IpNetwork, Networks};
 
@@ -8,8 +14,12 @@ pub struct NetworkData {
     pub interface: String,
     //Physical address of interface
     pub mac_address: String,
-    //IPv4
-    pub ip_network: String,
+    //Classified from sysfs
+    pub kind: InterfaceKind,
+    //Left out by the include/exclude rules, still tracked
+    pub hidden: bool,
+    //Every address, IPv4 first then IPv6
+    pub addresses: Vec<InterfaceAddress>,
     //Bytes
     pub total_received: u64,
     pub total_transmitted: u64,
@@ -17,6 +27,46 @@ pub struct NetworkData {
     pub rx_rate: f64,
     //Transmitted per secondes
     pub tx_rate: f64,
+    //Highest smoothed rates since Rusys started
+    pub rx_peak: f64,
+    pub tx_peak: f64,
+    //Packets, errors, drops... per second
+    pub packets: PacketRates,
+    //operstate, speed, duplex, MTU
+    pub link: LinkInfo,
+    //Highest link speed seen since Rusys started, Mb/s
+    pub best_speed: Option<u64>,
+}
+
+//Interface events kept for the UI
+const MAX_EVENTS: usize = 100;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum InterfaceEventKind {
+    Appeared,
+    Disappeared,
+    //Counters went backwards without the interface being recreated
+    CounterReset,
+    //Same name, new ifindex: deleted and created again between two ticks, ex: driver reload
+    Recreated,
+}
+
+impl InterfaceEventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            InterfaceEventKind::Appeared => "appeared",
+            InterfaceEventKind::Disappeared => "disappeared",
+            InterfaceEventKind::CounterReset => "counters reset",
+            InterfaceEventKind::Recreated => "recreated",
+        }
+    }
+}
+
+pub struct InterfaceEvent {
+    pub kind: InterfaceEventKind,
+    //When the tick noticed it
+    pub at: Instant,
+    pub interface: String,
 }
 
 //Network statistics
@@ -29,114 +79,266 @@ pub struct NetworkMonitor {
     last_received: std::collections::HashMap<String, u64>,
     //-> String == Interface, u64 == bytes
     last_transmitted: std::collections::HashMap<String, u64>,
-    //History -> String == interface, speed_rx and speed_tx
-    history: std::collections::HashMap<String, Vec<(f64, f64)>>,
+    //-> String == Interface, packet and error counters
+    last_counters: std::collections::HashMap<String, InterfaceCounters>,
+    //-> String == interface, rx and tx smoothing, see rates.smoothing
+    smoothers: std::collections::HashMap<String, (Smoother, Smoother)>,
+    smoothing: Smoothing,
+    //Long history for the charts -> String == interface, (when, avg_rx, avg_tx)
+    rate_history: std::collections::HashMap<String, VecDeque<(Instant, f64, f64)>>,
+    //Samples kept in rate_history
+    history_length: usize,
+    //-> String == Interface, highest negotiated speed seen in Mb/s
+    best_speeds: std::collections::HashMap<String, u64>,
+    //-> String == Interface, ifindex from sysfs
+    indexes: std::collections::HashMap<String, u32>,
+    //-> String == Interface, classified once when first seen
+    kinds: std::collections::HashMap<String, InterfaceKind>,
+    //Interface globs, see Config
+    include: Vec<String>,
+    exclude: Vec<String>,
+    //Newest last
+    events: VecDeque<InterfaceEvent>,
+    //Interfaces present at the first update aren't reported as appearing
+    primed: bool,
 }
 
 impl NetworkMonitor {
     //New NetworkMonitor Instance for tracking
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
         NetworkMonitor {
             //Each value start with n 0
             networks: Networks::new(),
             last_update: Instant::now(),
             last_received: std::collections::HashMap::new(),
             last_transmitted: std::collections::HashMap::new(),
-            history: std::collections::HashMap::new(),
+            last_counters: std::collections::HashMap::new(),
+            smoothers: std::collections::HashMap::new(),
+            smoothing: config.rate_smoothing,
+            rate_history: std::collections::HashMap::new(),
+            history_length: config.net_history_length,
+            best_speeds: std::collections::HashMap::new(),
+            indexes: std::collections::HashMap::new(),
+            kinds: std::collections::HashMap::new(),
+            include: config.net_include.clone(),
+            exclude: config.net_exclude.clone(),
+            events: VecDeque::new(),
+            primed: false,
         }
     }
-    //Get IPv4. Don't show IPv6 (U can print IPva4 && IPv6)
-    //-> &[IpNetwork] reference to the IP address array
+
+    //Smoothed rates of an interface, oldest first
+    pub fn rate_history(&self, interface: &str) -> impl DoubleEndedIterator<Item = &(Instant, f64, f64)> {
+        self.rate_history.get(interface).into_iter().flatten()
+    }
+    //All addresses of the interface, IPv4 first, then by scope
     //See sysinfo::IpNetwork
-    fn get_ipv4(ip_networks: &[IpNetwork]) -> String {
-        ip_networks
-            .iter()
-            //Search string contains .
-            //Example IPv6 -> .find(|network| network.to_string().contains(':'))
-            .find(|network| network.to_string().contains('.'))
-            //map_or_else ?
-            //map_or method's better;
-            //|ip == element|
-            .map_or("No IPv4".to_string(), |ip| ip.to_string())
+    fn get_addresses(ip_networks: &[IpNetwork]) -> Vec<InterfaceAddress> {
+        let mut addresses: Vec<InterfaceAddress> = ip_networks.iter().map(InterfaceAddress::from_network).collect();
+        addresses.sort_by_key(|address| (address.address.is_ipv6(), address.scope as u8, address.address));
+        addresses
+    }
+
+    //Appeared / disappeared / reset interfaces, newest first
+    pub fn events(&self) -> impl Iterator<Item = &InterfaceEvent> {
+        self.events.iter().rev()
+    }
+
+    fn push_event(&mut self, kind: InterfaceEventKind, at: Instant, interface: &str) {
+        self.events.push_back(InterfaceEvent {
+            kind,
+            at,
+            interface: interface.to_string(),
+        });
+        while self.events.len() > MAX_EVENTS {
+            self.events.pop_front();
+        }
     }
 
     //Return collections of stats for each interface
-    pub fn get_network_info(&mut self) -> Vec<NetworkData> {
+    //None when called again too soon to compute rates
+    pub fn get_network_info(&mut self) -> Option<Vec<NetworkData>> {
         let mut network_data = Vec::new();
-        //See sysinfo::Networks
-        self.networks.refresh(true);
-
         let now = Instant::now();
         let elapsed = now.duration_since(self.last_update).as_secs_f64();
 
         //Updating values
         if elapsed < 0.1 {
-            //Empty Vec
-            return network_data;
+            return None;
         }
+        //See sysinfo::Networks, true drops interfaces that are gone
+        self.networks.refresh(true);
 
-        for (interface_name, network) in self.networks.iter() {
-            if !is_phy(interface_name) {
-                continue;
+        //Forget everything about removed interfaces, a new one with the same name starts fresh
+        let gone: Vec<String> = self
+            .last_received
+            .keys()
+            .filter(|interface| !self.networks.contains_key(interface.as_str()))
+            .cloned()
+            .collect();
+        for interface in &gone {
+            if self.is_reported(interface) {
+                self.push_event(InterfaceEventKind::Disappeared, now, interface);
             }
+            self.forget(interface);
+        }
+        let appeared: Vec<String> = self
+            .networks
+            .keys()
+            .filter(|interface| !self.last_received.contains_key(interface.as_str()))
+            .cloned()
+            .collect();
+        if self.primed {
+            for interface in &appeared {
+                if self.is_reported(interface) {
+                    self.push_event(InterfaceEventKind::Appeared, now, interface);
+                }
+            }
+        }
+        self.primed = true;
+        //A recreated interface starts fresh like a new one, its counters have nothing to do with the old ones
+        let indexes: Vec<(String, u32)> = self
+            .networks
+            .keys()
+            .filter_map(|interface| Some((interface.clone(), interface_index(interface)?)))
+            .collect();
+        for (interface, index) in indexes {
+            if self.indexes.get(&interface).is_some_and(|last| *last != index) {
+                self.forget(&interface);
+                if self.is_reported(&interface) {
+                    self.push_event(InterfaceEventKind::Recreated, now, &interface);
+                }
+            }
+            self.indexes.insert(interface, index);
+        }
+        let mut resets = Vec::new();
+
+        for (interface_name, network) in self.networks.iter() {
             //Create new variables with rrx && ttx
             let current_rx = network.total_received();
             let current_tx = network.total_transmitted();
 
-            //Calculate tx
-            let (rx_rate, tx_rate) = match (
+            let counters = InterfaceCounters::read(interface_name);
+            let last = match (
                 self.last_received.get(interface_name.as_str()),
                 self.last_transmitted.get(interface_name.as_str()),
             ) {
-                (Some(&last_rx), Some(&last_tx)) => {
-                    let rx_diff = current_rx.saturating_sub(last_rx);
-                    let tx_diff = current_tx.saturating_sub(last_tx);
+                (Some(&last_rx), Some(&last_tx)) => Some((last_rx, last_tx)),
+                _ => None,
+            };
+            //Any counter going backwards: the deltas are meaningless for this tick
+            let reset = last.is_some_and(|(last_rx, last_tx)| current_rx < last_rx || current_tx < last_tx)
+                || matches!(
+                    (&counters, self.last_counters.get(interface_name.as_str())),
+                    (Some(counters), Some(last)) if counters.is_reset(last)
+                );
+            if reset {
+                resets.push(interface_name.clone());
+            }
+
+            let packets = match (&counters, self.last_counters.get(interface_name.as_str())) {
+                (Some(counters), Some(last)) if !reset => counters.rates(last, elapsed),
+                _ => PacketRates::default(),
+            };
 
-                    let rx = rx_diff as f64 / elapsed;
-                    let tx = tx_diff as f64 / elapsed;
-                    (rx, tx)
+            let (rx_smoother, tx_smoother) = self
+                .smoothers
+                .entry(interface_name.clone())
+                .or_insert_with(|| (Smoother::new(self.smoothing), Smoother::new(self.smoothing)));
+            let (avg_rx, avg_tx) = match last {
+                Some((last_rx, last_tx)) if !reset => {
+                    let rx = (current_rx - last_rx) as f64 / elapsed;
+                    let tx = (current_tx - last_tx) as f64 / elapsed;
+                    (rx_smoother.update(rx, elapsed), tx_smoother.update(tx, elapsed))
                 }
-                _ => (0.0, 0.0),
+                //Reset: keep showing the previous rates, the next tick has a clean baseline
+                Some(_) => (rx_smoother.value(), tx_smoother.value()),
+                //First sight of the interface has no rate yet, don't drag the average down with it
+                None => (0.0, 0.0),
             };
+            let (rx_peak, tx_peak) = (rx_smoother.peak(), tx_smoother.peak());
 
-            let history = self
-                .history
+            let rate_history = self
+                .rate_history
                 .entry(interface_name.clone())
-                .or_insert_with(Vec::new);
-            //Create history with 3 last values
-            //Average with 3 lasts values
-            //3 are optimise way?
-            history.push((rx_rate, tx_rate));
-            if history.len() > 3 {
-                history.remove(0);
+                .or_default();
+            rate_history.push_back((now, avg_rx, avg_tx));
+            while rate_history.len() > self.history_length {
+                rate_history.pop_front();
             }
 
-            let avg_rx = history.iter().map(|(rx, _)| rx).sum::<f64>() / history.len() as f64;
-            let avg_tx = history.iter().map(|(_, tx)| tx).sum::<f64>() / history.len() as f64;
-
             //Update datas
             self.last_received
                 .insert(interface_name.to_string(), current_rx);
             self.last_transmitted
                 .insert(interface_name.to_string(), current_tx);
+            match counters {
+                Some(counters) => self.last_counters.insert(interface_name.to_string(), counters),
+                None => self.last_counters.remove(interface_name.as_str()),
+            };
 
-            //Call get_ipv4 function
-            let ipv4 = Self::get_ipv4(network.ip_networks());
+            let addresses = Self::get_addresses(network.ip_networks());
+            let kind = *self
+                .kinds
+                .entry(interface_name.clone())
+                .or_insert_with(|| interface_kind(interface_name));
+            let link = LinkInfo::read(interface_name);
+            let best_speed = match link.speed {
+                Some(speed) => Some(*self
+                    .best_speeds
+                    .entry(interface_name.clone())
+                    .and_modify(|best| *best = (*best).max(speed))
+                    .or_insert(speed)),
+                None => self.best_speeds.get(interface_name.as_str()).copied(),
+            };
             //Push values to Vec
             //Please refeere to the NetworkData struct;
             //If u can add more datas u need pass here
             network_data.push(NetworkData {
                 interface: interface_name.to_string(),
+                kind,
+                hidden: !is_selected(interface_name, kind, &self.include, &self.exclude),
                 mac_address: network.mac_address().to_string(),
-                ip_network: ipv4,
+                addresses,
                 total_received: current_rx,
                 total_transmitted: current_tx,
                 rx_rate: avg_rx,
                 tx_rate: avg_tx,
+                rx_peak,
+                tx_peak,
+                packets,
+                link,
+                best_speed,
             });
         }
+        for interface in &resets {
+            if self.is_reported(interface) {
+                self.push_event(InterfaceEventKind::CounterReset, now, interface);
+            }
+        }
         //Updated
         self.last_update = now;
-        network_data
+        Some(network_data)
+    }
+
+    //Drop the per-interface state of a removed interface
+    fn forget(&mut self, interface: &str) {
+        self.last_received.remove(interface);
+        self.last_transmitted.remove(interface);
+        self.last_counters.remove(interface);
+        self.smoothers.remove(interface);
+        self.rate_history.remove(interface);
+        self.best_speeds.remove(interface);
+        self.indexes.remove(interface);
+        self.kinds.remove(interface);
+    }
+
+    //Events are only kept for interfaces the network panel shows
+    fn is_reported(&mut self, interface: &str) -> bool {
+        let kind = *self
+            .kinds
+            .entry(interface.to_string())
+            .or_insert_with(|| interface_kind(interface));
+        is_selected(interface, kind, &self.include, &self.exclude)
     }
 }
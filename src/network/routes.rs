@@ -0,0 +1,221 @@
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+//Route flags from include/uapi/linux/route.h and ipv6_route.h
+const RTF_UP: u32 = 0x0001;
+const RTF_REJECT: u32 = 0x0200;
+const RTF_LOCAL: u32 = 0x8000_0000;
+
+///Routing table entry
+pub struct Route {
+    pub destination: IpAddr,
+    pub prefix: u8,
+    //None == directly connected
+    pub gateway: Option<IpAddr>,
+    pub interface: String,
+    pub metric: u32,
+}
+
+impl Route {
+    pub fn is_default(&self) -> bool {
+        self.prefix == 0
+    }
+}
+
+///ARP table entry
+pub struct Neighbor {
+    pub address: IpAddr,
+    pub mac: String,
+    pub interface: String,
+    //Flags 0x2 == resolved, incomplete entries are still being asked for
+    pub complete: bool,
+}
+
+///Resolver configuration
+#[derive(Default)]
+pub struct DnsConfig {
+    pub nameservers: Vec<String>,
+    pub search: Vec<String>,
+    pub options: Vec<String>,
+}
+
+//IPv4 then IPv6 routes of the main tables, default routes first
+pub fn get_routes() -> Vec<Route> {
+    let mut routes = parse_ipv4_routes(&fs::read_to_string("/proc/net/route").unwrap_or_default());
+    routes.extend(parse_ipv6_routes(&fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default()));
+    routes.sort_by_key(|route| (route.destination.is_ipv6(), !route.is_default(), route.metric));
+    routes
+}
+
+//Iface Destination Gateway Flags RefCnt Use Metric Mask MTU Window IRTT
+//eth0  00000000    010200C0 0003 0      0   0      00000000 ...
+fn parse_ipv4_routes(content: &str) -> Vec<Route> {
+    let mut routes = Vec::new();
+    for line in content.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 8 {
+            continue;
+        }
+        //In memory order, little endian on most hosts
+        let hex_v4 = |field: &str| u32::from_str_radix(field, 16).ok().map(|value| Ipv4Addr::from(value.to_ne_bytes()));
+        let (Some(destination), Some(gateway), Some(mask)) = (hex_v4(fields[1]), hex_v4(fields[2]), hex_v4(fields[7]))
+        else {
+            continue;
+        };
+        let flags = u32::from_str_radix(fields[3], 16).unwrap_or(0);
+        if flags & RTF_UP == 0 || flags & RTF_REJECT != 0 {
+            continue;
+        }
+        routes.push(Route {
+            destination: IpAddr::V4(destination),
+            prefix: u32::from(mask).count_ones() as u8,
+            gateway: Some(IpAddr::V4(gateway)).filter(|gateway| !gateway.is_unspecified()),
+            interface: fields[0].to_string(),
+            metric: fields[6].parse().unwrap_or(0),
+        });
+    }
+    routes
+}
+
+//dest prefix src src_prefix nexthop metric refcnt use flags iface, all hex
+fn parse_ipv6_routes(content: &str) -> Vec<Route> {
+    let mut routes = Vec::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        //Network order, unlike /proc/net/route
+        let hex_v6 = |field: &str| u128::from_str_radix(field, 16).ok().map(Ipv6Addr::from);
+        let (Some(destination), Some(gateway)) = (hex_v6(fields[0]), hex_v6(fields[4])) else {
+            continue;
+        };
+        let flags = u32::from_str_radix(fields[8], 16).unwrap_or(0);
+        //Local table and multicast routes, ip -6 route doesn't show them either
+        if flags & RTF_UP == 0 || flags & (RTF_REJECT | RTF_LOCAL) != 0 || destination.is_multicast() {
+            continue;
+        }
+        routes.push(Route {
+            destination: IpAddr::V6(destination),
+            prefix: u8::from_str_radix(fields[1], 16).unwrap_or(0),
+            gateway: Some(IpAddr::V6(gateway)).filter(|gateway| !gateway.is_unspecified()),
+            interface: fields[9].to_string(),
+            metric: u32::from_str_radix(fields[5], 16).unwrap_or(0),
+        });
+    }
+    routes
+}
+
+//IPv4 neighbors, IPv6 ones are only exposed over netlink
+pub fn get_neighbors() -> Vec<Neighbor> {
+    parse_neighbors(&fs::read_to_string("/proc/net/arp").unwrap_or_default())
+}
+
+//IP address  HW type  Flags  HW address         Mask  Device
+//192.0.2.1   0x1      0x2    02:fc:00:00:00:05  *     eth0
+fn parse_neighbors(content: &str) -> Vec<Neighbor> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            let flags = u32::from_str_radix(fields[2].trim_start_matches("0x"), 16).unwrap_or(0);
+            Some(Neighbor {
+                address: fields[0].parse().ok()?,
+                mac: fields[3].to_string(),
+                interface: fields[5].to_string(),
+                complete: flags & 0x2 != 0,
+            })
+        })
+        .collect()
+}
+
+pub fn get_dns_config() -> DnsConfig {
+    let mut dns = DnsConfig::default();
+    let content = fs::read_to_string("/etc/resolv.conf").unwrap_or_default();
+    for line in content.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("nameserver") => dns.nameservers.extend(words.next().map(String::from)),
+            //The last of search / domain wins, like the resolver
+            Some("search") | Some("domain") => dns.search = words.map(String::from).collect(),
+            Some("options") => dns.options.extend(words.map(String::from)),
+            _ => {}
+        }
+    }
+    dns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Addresses as the kernel prints them: the u32 in memory order
+    fn hex_v4(address: [u8; 4]) -> String {
+        format!("{:08X}", u32::from_ne_bytes(address))
+    }
+
+    #[test]
+    fn ipv4_default_and_connected_routes() {
+        let content = format!(
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+             eth0\t{any}\t{gateway}\t0003\t0\t0\t100\t{any}\t0\t0\t0\n\
+             eth0\t{network}\t{any}\t0001\t0\t0\t0\t{mask}\t0\t0\t0\n\
+             eth0\t{blocked}\t{any}\t0201\t0\t0\t0\t{host}\t0\t0\t0\n\
+             eth1\t{network}\t{any}\t0000\t0\t0\t0\t{mask}\t0\t0\t0\n",
+            any = hex_v4([0, 0, 0, 0]),
+            gateway = hex_v4([192, 0, 2, 1]),
+            network = hex_v4([192, 0, 2, 0]),
+            mask = hex_v4([255, 255, 255, 0]),
+            blocked = hex_v4([198, 51, 100, 7]),
+            host = hex_v4([255, 255, 255, 255]),
+        );
+        let routes = parse_ipv4_routes(&content);
+        //Reject route and the route that isn't up are left out
+        assert_eq!(routes.len(), 2);
+        assert!(routes[0].is_default());
+        assert_eq!(routes[0].gateway, Some("192.0.2.1".parse().unwrap()));
+        assert_eq!((routes[0].interface.as_str(), routes[0].metric), ("eth0", 100));
+        assert_eq!(routes[1].destination, "192.0.2.0".parse::<IpAddr>().unwrap());
+        assert_eq!(routes[1].prefix, 24);
+        assert_eq!(routes[1].gateway, None);
+    }
+
+    #[test]
+    fn ipv6_routes() {
+        let content = "\
+fd000000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fd000000000000000000000000000001 00000400 00000001 00000000 00000003     eth0
+00000000000000000000000000000001 80 00000000000000000000000000000000 00 00000000000000000000000000000000 00000000 00000003 00000000 80200001       lo
+ff000000000000000000000000000000 08 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000004 00000000 00000001     eth0
+";
+        let routes = parse_ipv6_routes(content);
+        //Local ::1 and multicast ff00::/8 are left out
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].destination, "fd00::".parse::<IpAddr>().unwrap());
+        assert_eq!((routes[0].prefix, routes[0].metric), (64, 256));
+        assert_eq!(routes[0].gateway, None);
+        assert!(routes[1].is_default());
+        assert_eq!(routes[1].gateway, Some("fd00::1".parse().unwrap()));
+        assert_eq!(routes[1].metric, 1024);
+    }
+
+    #[test]
+    fn neighbors_complete_and_incomplete() {
+        let content = "\
+IP address       HW type     Flags       HW address            Mask     Device
+192.0.2.1        0x1         0x2         02:fc:00:00:00:05     *        eth0
+192.0.2.9        0x1         0x0         00:00:00:00:00:00     *        eth0
+not-an-address   0x1         0x2         02:fc:00:00:00:06     *        eth0
+";
+        let neighbors = parse_neighbors(content);
+        assert_eq!(neighbors.len(), 2);
+        assert!(neighbors[0].complete);
+        assert_eq!(neighbors[0].mac, "02:fc:00:00:00:05");
+        assert!(!neighbors[1].complete);
+        assert_eq!(neighbors[1].address, "192.0.2.9".parse::<IpAddr>().unwrap());
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+//Walking every /proc/<pid>/fd is the expensive part, sockets seen in between show no process until then
+const OWNERS_EVERY: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+    Unix,
+}
+
+impl Protocol {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Tcp6 => "tcp6",
+            Protocol::Udp => "udp",
+            Protocol::Udp6 => "udp6",
+            Protocol::Unix => "unix",
+        }
+    }
+}
+
+//One end of a socket
+#[derive(Clone)]
+pub enum Endpoint {
+    Inet(SocketAddr),
+    //Path, @name for abstract sockets, empty when unnamed
+    Unix(String),
+}
+
+impl Endpoint {
+    pub fn port(&self) -> Option<u16> {
+        match self {
+            Endpoint::Inet(addr) => Some(addr.port()),
+            Endpoint::Unix(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            //0.0.0.0:* like ss for the unconnected side
+            Endpoint::Inet(addr) if addr.port() == 0 && addr.ip().is_unspecified() => write!(f, "{}:*", addr.ip()),
+            Endpoint::Inet(addr) => write!(f, "{}", addr),
+            Endpoint::Unix(path) if path.is_empty() => write!(f, "*"),
+            Endpoint::Unix(path) => write!(f, "{}", path),
+        }
+    }
+}
+
+///Socket snapshot, like a line of ss
+pub struct SocketInfo {
+    pub protocol: Protocol,
+    pub local: Endpoint,
+    pub remote: Endpoint,
+    //ss state names: ESTAB, LISTEN, TIME-WAIT, UNCONN...
+    pub state: &'static str,
+    //Bytes waiting to be read by the application / acknowledged by the peer
+    //None for Unix sockets, /proc/net/unix doesn't report them
+    pub recv_queue: Option<u64>,
+    pub send_queue: Option<u64>,
+    //Owning process, None when the socket belongs to another user or is orphaned
+    pub pid: Option<u32>,
+    //Owner from the socket uid, None for Unix sockets
+    pub user: Option<String>,
+}
+
+impl SocketInfo {
+    //Accepting connections (TCP) or bound waiting for datagrams (UDP)
+    pub fn is_listening(&self) -> bool {
+        match self.protocol {
+            Protocol::Tcp | Protocol::Tcp6 => self.state == "LISTEN",
+            Protocol::Udp | Protocol::Udp6 => self.state == "UNCONN",
+            Protocol::Unix => false,
+        }
+    }
+
+    //Bound to 0.0.0.0 or ::, reachable from every interface
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self.local, Endpoint::Inet(addr) if addr.ip().is_unspecified())
+    }
+}
+
+//Socket table, owning processes and user names refreshed every OWNERS_EVERY
+pub struct SocketMonitor {
+    last_owners: Option<Instant>,
+    //-> u64 == socket inode, u32 == pid holding it
+    owners: HashMap<u64, u32>,
+    //-> u32 == uid, String == login
+    users: HashMap<u32, String>,
+}
+
+impl SocketMonitor {
+    pub fn new() -> Self {
+        SocketMonitor {
+            last_owners: None,
+            owners: HashMap::new(),
+            users: HashMap::new(),
+        }
+    }
+
+    //Every socket from /proc/net, sorted by protocol then local endpoint
+    pub fn get_sockets(&mut self) -> Vec<SocketInfo> {
+        let now = Instant::now();
+        let stale = match self.last_owners {
+            Some(last) => now.duration_since(last) >= OWNERS_EVERY,
+            None => true,
+        };
+        if stale {
+            self.last_owners = Some(now);
+            self.owners = socket_owners();
+            self.users = user_names();
+        }
+        read_sockets(&self.owners, &self.users)
+    }
+}
+
+fn read_sockets(owners: &HashMap<u64, u32>, users: &HashMap<u32, String>) -> Vec<SocketInfo> {
+    let mut sockets = Vec::new();
+    for (protocol, file) in [
+        (Protocol::Tcp, "/proc/net/tcp"),
+        (Protocol::Tcp6, "/proc/net/tcp6"),
+        (Protocol::Udp, "/proc/net/udp"),
+        (Protocol::Udp6, "/proc/net/udp6"),
+    ] {
+        let content = fs::read_to_string(file).unwrap_or_default();
+        sockets.extend(content.lines().skip(1).filter_map(|line| parse_inet(protocol, line, owners, users)));
+    }
+    let content = fs::read_to_string("/proc/net/unix").unwrap_or_default();
+    sockets.extend(content.lines().skip(1).filter_map(|line| parse_unix(line, owners)));
+
+    sockets.sort_by(|a, b| {
+        (a.protocol as u8)
+            .cmp(&(b.protocol as u8))
+            .then(a.local.port().cmp(&b.local.port()))
+            .then(a.local.to_string().cmp(&b.local.to_string()))
+    });
+    sockets
+}
+
+//  sl  local_address rem_address   st tx_queue:rx_queue tr:tm->when retrnsmt   uid  timeout inode
+//   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 23456 ...
+fn parse_inet(
+    protocol: Protocol,
+    line: &str,
+    owners: &HashMap<u64, u32>,
+    users: &HashMap<u32, String>,
+) -> Option<SocketInfo> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 10 {
+        return None;
+    }
+    let (send_queue, recv_queue) = fields[4].split_once(':')?;
+    let uid: Option<u32> = fields[7].parse().ok();
+    let inode: u64 = fields[9].parse().ok()?;
+    let state = u8::from_str_radix(fields[3], 16).ok()?;
+    let state = match protocol {
+        //Datagram sockets only use ESTABLISHED and CLOSE
+        Protocol::Udp | Protocol::Udp6 if state == 0x01 => "ESTAB",
+        Protocol::Udp | Protocol::Udp6 => "UNCONN",
+        _ => tcp_state(state),
+    };
+    Some(SocketInfo {
+        protocol,
+        local: Endpoint::Inet(parse_address(fields[1])?),
+        remote: Endpoint::Inet(parse_address(fields[2])?),
+        state,
+        recv_queue: u64::from_str_radix(recv_queue, 16).ok(),
+        send_queue: u64::from_str_radix(send_queue, 16).ok(),
+        pid: owners.get(&inode).copied(),
+        user: uid.map(|uid| users.get(&uid).cloned().unwrap_or_else(|| uid.to_string())),
+    })
+}
+
+//include/net/tcp_states.h
+fn tcp_state(state: u8) -> &'static str {
+    match state {
+        0x01 => "ESTAB",
+        0x02 => "SYN-SENT",
+        0x03 => "SYN-RECV",
+        0x04 => "FIN-WAIT-1",
+        0x05 => "FIN-WAIT-2",
+        0x06 => "TIME-WAIT",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE-WAIT",
+        0x09 => "LAST-ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        0x0C => "NEW-SYN-RECV",
+        _ => "UNKNOWN",
+    }
+}
+
+//Hex address:port, the address is the raw in-memory value, 32 bits at a time
+fn parse_address(field: &str) -> Option<SocketAddr> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let words: Vec<[u8; 4]> = (0..address.len() / 8)
+        .map(|i| u32::from_str_radix(&address[i * 8..i * 8 + 8], 16).map(u32::to_ne_bytes))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let ip = match words[..] {
+        [word] => IpAddr::V4(Ipv4Addr::from(word)),
+        [a, b, c, d] => {
+            let mut bytes = [0u8; 16];
+            for (chunk, word) in bytes.chunks_mut(4).zip([a, b, c, d]) {
+                chunk.copy_from_slice(&word);
+            }
+            IpAddr::V6(Ipv6Addr::from(bytes))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+//Num       RefCount Protocol Flags    Type St Inode Path
+//0000000000000000: 00000002 00000000 00010000 0001 01 23456 /run/dbus/system_bus_socket
+fn parse_unix(line: &str, owners: &HashMap<u64, u32>) -> Option<SocketInfo> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 7 {
+        return None;
+    }
+    let flags = u32::from_str_radix(fields[3], 16).ok()?;
+    let inode: u64 = fields[6].parse().ok()?;
+    //__SO_ACCEPTCON, set by listen()
+    let state = if flags & 0x0001_0000 != 0 {
+        "LISTEN"
+    } else {
+        match fields[5] {
+            "02" => "CONNECTING",
+            "03" => "ESTAB",
+            "04" => "DISCONNECTING",
+            _ => "UNCONN",
+        }
+    };
+    Some(SocketInfo {
+        protocol: Protocol::Unix,
+        local: Endpoint::Unix(fields.get(7).map_or(String::new(), |path| path.to_string())),
+        remote: Endpoint::Unix(String::new()),
+        state,
+        recv_queue: None,
+        send_queue: None,
+        pid: owners.get(&inode).copied(),
+        user: None,
+    })
+}
+
+//-> u32 == uid, String == login from /etc/passwd
+fn user_names() -> HashMap<u32, String> {
+    fs::read_to_string("/etc/passwd")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            //name:password:uid:gid:...
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+//-> u64 == socket inode, u32 == pid holding it
+//Only readable for our own processes unless running as root
+fn socket_owners() -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return owners;
+    };
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            //socket:[23456]
+            let inode = target
+                .to_str()
+                .and_then(|target| target.strip_prefix("socket:["))
+                .and_then(|target| target.strip_suffix(']'))
+                .and_then(|inode| inode.parse().ok());
+            if let Some(inode) = inode {
+                owners.entry(inode).or_insert(pid);
+            }
+        }
+    }
+    owners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_address_ipv4() {
+        //127.0.0.1:631, bytes in memory order
+        let address = parse_address("0100007F:0277").unwrap();
+        assert_eq!(address, "127.0.0.1:631".parse().unwrap());
+        assert_eq!(parse_address("00000000:0000").unwrap(), "0.0.0.0:0".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_address_ipv6() {
+        let loopback = parse_address("00000000000000000000000001000000:0016").unwrap();
+        assert_eq!(loopback, "[::1]:22".parse().unwrap());
+        //fe80::1
+        let link_local = parse_address("000080FE000000000000000001000000:01BB").unwrap();
+        assert_eq!(link_local, "[fe80::1]:443".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_address_rejects_garbage() {
+        assert!(parse_address("0100007F").is_none());
+        assert!(parse_address("0100007F:zz").is_none());
+        assert!(parse_address("0100:0016").is_none());
+        assert!(parse_address("XX00007F:0016").is_none());
+    }
+
+    #[test]
+    fn tcp_state_names() {
+        assert_eq!(tcp_state(0x01), "ESTAB");
+        assert_eq!(tcp_state(0x06), "TIME-WAIT");
+        assert_eq!(tcp_state(0x0A), "LISTEN");
+        assert_eq!(tcp_state(0x0C), "NEW-SYN-RECV");
+        assert_eq!(tcp_state(0x00), "UNKNOWN");
+        assert_eq!(tcp_state(0xFF), "UNKNOWN");
+    }
+
+    #[test]
+    fn parse_unix_listening_with_path() {
+        let owners = HashMap::from([(23456, 42)]);
+        let socket =
+            parse_unix("0000000000000000: 00000002 00000000 00010000 0001 01 23456 /run/dbus/system_bus_socket", &owners)
+                .unwrap();
+        assert_eq!(socket.state, "LISTEN");
+        assert_eq!(socket.local.to_string(), "/run/dbus/system_bus_socket");
+        assert_eq!(socket.pid, Some(42));
+        assert!(socket.recv_queue.is_none() && socket.send_queue.is_none());
+    }
+
+    #[test]
+    fn parse_unix_unnamed_and_connected() {
+        let socket = parse_unix("0000000000000000: 00000003 00000000 00000000 0001 03 777", &HashMap::new()).unwrap();
+        assert_eq!(socket.state, "ESTAB");
+        assert_eq!(socket.local.to_string(), "*");
+        assert_eq!(socket.pid, None);
+        assert!(parse_unix("0000000000000000: 00000003 00000000", &HashMap::new()).is_none());
+    }
+}
@@ -1,28 +1,143 @@
-use nix::net::if_::InterfaceFlags;
+use std::fs;
+use std::path::Path;
+use nix::sys::socket::SockProtocol;
+use crate::config::glob_match;
+use super::netlink::{attribute, request, NLM_F_REQUEST};
 
-fn prefixes(pre: &str) -> bool
+//From include/uapi/linux/rtnetlink.h and if_link.h
+const RTM_NEWLINK: u16 = 16;
+const RTM_GETLINK: u16 = 18;
+const IFLA_LINKINFO: u16 = 18;
+const IFLA_INFO_KIND: u16 = 1;
+//struct ifinfomsg
+const IFINFO_LEN: usize = 16;
+
+//What an interface is, from /sys/class/net/<if>
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum InterfaceKind
 {
-    //Dont show interfaces with this prefixes->
-    //More ?
-    let pref = ["docker", "veth", "br-", "virbr", "tun", "vmnet", "vboxnet", "wg", "bond", "dummy"];
-    !pref.iter().any(|prefix| pre.starts_with(prefix))
+    Physical,
+    Wireless,
+    Bridge,
+    Bond,
+    Vlan,
+    Veth,
+    Tunnel,
+    WireGuard,
+    Loopback,
+    //No device behind it and nothing more specific, ex: dummy, ifb
+    Virtual,
 }
 
-pub fn is_phy(name: &str) -> bool
+impl InterfaceKind
 {
-    if !prefixes(name)
+    pub fn label(&self) -> &'static str
     {
-        return false;
+        match self
+        {
+            InterfaceKind::Physical => "physical",
+            InterfaceKind::Wireless => "wireless",
+            InterfaceKind::Bridge => "bridge",
+            InterfaceKind::Bond => "bond",
+            InterfaceKind::Vlan => "vlan",
+            InterfaceKind::Veth => "veth",
+            InterfaceKind::Tunnel => "tunnel",
+            InterfaceKind::WireGuard => "wireguard",
+            InterfaceKind::Loopback => "loopback",
+            InterfaceKind::Virtual => "virtual",
+        }
+    }
+}
+
+//Unreadable sysfs (interface gone meanwhile) falls back to Virtual
+pub fn interface_kind(name: &str) -> InterfaceKind
+{
+    let dir = Path::new("/sys/class/net").join(name);
+    let read = |file: &str| fs::read_to_string(dir.join(file)).map(|value| value.trim().to_string()).unwrap_or_default();
+
+    //ARPHRD_* from include/uapi/linux/if_arp.h
+    let link_type: u32 = read("type").parse().unwrap_or(0);
+    let devtype = read("uevent")
+    .lines()
+    .find_map(|line| line.strip_prefix("DEVTYPE="))
+    .unwrap_or_default()
+    .to_string();
+
+    if link_type == 772
+    {
+        return InterfaceKind::Loopback;
+    }
+    match devtype.as_str()
+    {
+        "wireguard" => return InterfaceKind::WireGuard,
+        "wlan" => return InterfaceKind::Wireless,
+        "bridge" => return InterfaceKind::Bridge,
+        "bond" => return InterfaceKind::Bond,
+        "vlan" => return InterfaceKind::Vlan,
+        _ => {}
+    }
+    if dir.join("wireless").exists() || dir.join("phy80211").exists()
+    {
+        return InterfaceKind::Wireless;
+    }
+    if dir.join("bridge").exists()
+    {
+        return InterfaceKind::Bridge;
+    }
+    if dir.join("bonding").exists()
+    {
+        return InterfaceKind::Bond;
+    }
+    //none (tun), ipip, ip6tnl, sit, gre, ip6gre
+    if matches!(link_type, 65534 | 768 | 769 | 776 | 778 | 823)
+    {
+        return InterfaceKind::Tunnel;
+    }
+    if dir.join("device").exists()
+    {
+        return InterfaceKind::Physical;
+    }
+    //sysfs can't tell veth from macvlan / ipvlan / macvtap, the driver's link kind can
+    //No ifindex == already gone, no point asking netlink
+    match interface_index(name).and_then(link_kind).as_deref()
+    {
+        Some("veth") => InterfaceKind::Veth,
+        Some("vxlan" | "geneve" | "gretap" | "ip6gretap") => InterfaceKind::Tunnel,
+        _ => InterfaceKind::Virtual,
     }
+}
+
+//IFLA_INFO_KIND of an interface ("veth", "macvlan", ...) asked over rtnetlink
+//None when netlink isn't reachable or the driver doesn't report a kind
+fn link_kind(ifindex: u32) -> Option<String>
+{
+    //ifinfomsg {family, pad, type, index, flags, change}
+    let mut payload = vec![0u8; IFINFO_LEN];
+    payload[4..8].copy_from_slice(&ifindex.to_ne_bytes());
+    let messages = request(SockProtocol::NetlinkRoute, RTM_GETLINK, NLM_F_REQUEST, &payload)?;
+    let message = messages.iter().find(|message| message.kind == RTM_NEWLINK)?;
+    let link_info = attribute(message.payload.get(IFINFO_LEN..)?, IFLA_LINKINFO)?;
+    let kind = attribute(link_info, IFLA_INFO_KIND)?;
+    Some(String::from_utf8_lossy(kind).trim_end_matches('\0').to_string())
+}
 
-    let flags = nix::net::if_::InterfaceFlags::from_bits_truncate(
-        nix::ifaddrs::getifaddrs()
-        .unwrap()
-        .find(|x| x.interface_name == name)
-        .map(|x| x.flags.bits())
-        .unwrap_or(0)
-    );
+//ifindex from sysfs, None when the interface is gone
+pub fn interface_index(name: &str) -> Option<u32>
+{
+    fs::read_to_string(Path::new("/sys/class/net").join(name).join("ifindex")).ok()?.trim().parse().ok()
+}
 
-    !flags.contains(InterfaceFlags::IFF_LOOPBACK) &&
-    !flags.contains(InterfaceFlags::IFF_POINTOPOINT)
+//Interface picked by the network.include / network.exclude globs
+//Empty include == every interface, exclude applies after it
+pub fn is_selected(name: &str, kind: InterfaceKind, include: &[String], exclude: &[String]) -> bool
+{
+    if !include.is_empty() && !include.iter().any(|pattern| glob_match(pattern, name))
+    {
+        return false;
+    }
+    if exclude.iter().any(|pattern| glob_match(pattern, name))
+    {
+        return false;
+    }
+    kind != InterfaceKind::Loopback
 }
@@ -0,0 +1,215 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+
+use nix::sys::socket::SockProtocol;
+
+use super::netlink::{attribute, push_attribute, request, NLM_F_DUMP, NLM_F_REQUEST};
+use super::utils::interface_index;
+
+//cfg80211 reports quality out of 70 when the signal is in dBm (-110 to -40), out of 100 otherwise
+const QUALITY_SCALE_DBM: f64 = 70.0;
+const QUALITY_SCALE_UNSPEC: f64 = 100.0;
+
+//Generic netlink controller, include/uapi/linux/genetlink.h
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+//struct genlmsghdr {cmd, version, reserved}
+const GENL_HEADER_LEN: usize = 4;
+//include/uapi/linux/nl80211.h
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_GET_STATION: u8 = 17;
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_STA_INFO: u16 = 21;
+const NL80211_ATTR_SSID: u16 = 52;
+const NL80211_STA_INFO_TX_BITRATE: u16 = 8;
+const NL80211_RATE_INFO_BITRATE: u16 = 1;
+const NL80211_RATE_INFO_BITRATE32: u16 = 5;
+
+///Wireless link snapshot
+pub struct WirelessData {
+    pub interface: String,
+    //Link quality, percent of the driver's scale
+    pub quality: f64,
+    //dBm
+    pub signal: f64,
+    //dBm, None when the driver doesn't measure it
+    pub noise: Option<f64>,
+    //operstate "up", dormant while scanning or not associated
+    pub associated: bool,
+    //From nl80211, None when not associated or nl80211 isn't available
+    pub ssid: Option<String>,
+    //Mb/s, current transmit bitrate to the access point
+    pub bitrate: Option<f64>,
+}
+
+//Wi-Fi link quality from /proc/net/wireless, association state from sysfs, SSID and bitrate from nl80211
+pub struct WirelessMonitor {
+    //-> String == Interface, signal dBm, oldest first
+    history: HashMap<String, VecDeque<f64>>,
+    //Samples kept per interface
+    history_length: usize,
+    //Generic netlink id of nl80211, looked up once a wireless interface shows up
+    nl80211: Option<u16>,
+}
+
+impl WirelessMonitor {
+    pub fn new(history_length: usize) -> Self {
+        WirelessMonitor {
+            history: HashMap::new(),
+            history_length,
+            nl80211: None,
+        }
+    }
+
+    //Signal levels of an interface, oldest first
+    pub fn signal_history(&self, interface: &str) -> impl DoubleEndedIterator<Item = &f64> {
+        self.history.get(interface).into_iter().flatten()
+    }
+
+    pub fn get_wireless_info(&mut self) -> Vec<WirelessData> {
+        let mut wireless = Vec::new();
+        //Missing without wireless extensions or wireless interfaces
+        let content = fs::read_to_string("/proc/net/wireless").unwrap_or_default();
+
+        //Two header lines, then one per interface
+        for line in content.lines().skip(2) {
+            let Some(link) = parse_line(line) else {
+                continue;
+            };
+            let history = self.history.entry(link.interface.clone()).or_default();
+            history.push_back(link.signal);
+            while history.len() > self.history_length {
+                history.pop_front();
+            }
+
+            if self.nl80211.is_none() {
+                self.nl80211 = family_id("nl80211");
+            }
+            let index = interface_index(&link.interface);
+            let (ssid, bitrate) = match (self.nl80211, index) {
+                (Some(family), Some(index)) => (get_ssid(family, index), get_bitrate(family, index)),
+                _ => (None, None),
+            };
+            let operstate = fs::read_to_string(Path::new("/sys/class/net").join(&link.interface).join("operstate"))
+                .unwrap_or_default();
+            let scale = if link.signal < 0.0 { QUALITY_SCALE_DBM } else { QUALITY_SCALE_UNSPEC };
+            wireless.push(WirelessData {
+                associated: operstate.trim() == "up",
+                interface: link.interface,
+                quality: (link.quality / scale * 100.0).clamp(0.0, 100.0),
+                signal: link.signal,
+                //-256 == not measured
+                noise: Some(link.noise).filter(|noise| *noise > -256.0),
+                ssid,
+                bitrate,
+            });
+        }
+        //Unplugged or switched off
+        self.history
+            .retain(|interface, _| wireless.iter().any(|data| &data.interface == interface));
+        wireless
+    }
+}
+
+//Raw values of one /proc/net/wireless line
+struct Link {
+    interface: String,
+    quality: f64,
+    signal: f64,
+    noise: f64,
+}
+
+// wlan0: 0000   70.  -40.  -256        0      0      0      0      0        0
+//Status is hex, a trailing '.' marks a value updated since the last read
+fn parse_line(line: &str) -> Option<Link> {
+    let (interface, values) = line.split_once(':')?;
+    let mut values = values.split_whitespace().skip(1).map(|value| value.trim_end_matches('.').parse::<f64>());
+    Some(Link {
+        interface: interface.trim().to_string(),
+        quality: values.next()?.ok()?,
+        signal: values.next()?.ok()?,
+        noise: values.next()?.ok()?,
+    })
+}
+
+//Generic netlink request, None on any failure
+fn genl_request(family: u16, command: u8, flags: u16, attributes: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut payload = vec![command, 1, 0, 0];
+    payload.extend_from_slice(attributes);
+    let messages = request(SockProtocol::NetlinkGeneric, family, NLM_F_REQUEST | flags, &payload)?;
+    Some(
+        messages
+            .into_iter()
+            .filter(|message| message.kind == family)
+            .filter_map(|message| message.payload.get(GENL_HEADER_LEN..).map(<[u8]>::to_vec))
+            .collect(),
+    )
+}
+
+//Numeric id of a generic netlink family, None when its module isn't loaded
+fn family_id(name: &str) -> Option<u16> {
+    let mut attributes = Vec::new();
+    push_attribute(&mut attributes, CTRL_ATTR_FAMILY_NAME, format!("{}\0", name).as_bytes());
+    let answers = genl_request(GENL_ID_CTRL, CTRL_CMD_GETFAMILY, 0, &attributes)?;
+    let id = attribute(answers.first()?, CTRL_ATTR_FAMILY_ID)?;
+    Some(u16::from_ne_bytes(id.get(..2)?.try_into().ok()?))
+}
+
+fn ifindex_attribute(index: u32) -> Vec<u8> {
+    let mut attributes = Vec::new();
+    push_attribute(&mut attributes, NL80211_ATTR_IFINDEX, &index.to_ne_bytes());
+    attributes
+}
+
+//SSID of the network the interface is connected to
+fn get_ssid(family: u16, index: u32) -> Option<String> {
+    let answers = genl_request(family, NL80211_CMD_GET_INTERFACE, 0, &ifindex_attribute(index))?;
+    let ssid = attribute(answers.first()?, NL80211_ATTR_SSID)?;
+    Some(String::from_utf8_lossy(ssid).to_string()).filter(|ssid| !ssid.is_empty())
+}
+
+//Transmit bitrate to the first station, the access point in managed mode
+fn get_bitrate(family: u16, index: u32) -> Option<f64> {
+    let answers = genl_request(family, NL80211_CMD_GET_STATION, NLM_F_DUMP, &ifindex_attribute(index))?;
+    answers.iter().find_map(|answer| {
+        let station = attribute(answer, NL80211_ATTR_STA_INFO)?;
+        let rate = attribute(station, NL80211_STA_INFO_TX_BITRATE)?;
+        //Units of 100 kb/s, the 16 bit field saturates above 6.5 Gb/s
+        let bitrate = match attribute(rate, NL80211_RATE_INFO_BITRATE32) {
+            Some(value) => u32::from_ne_bytes(value.get(..4)?.try_into().ok()?),
+            None => u16::from_ne_bytes(attribute(rate, NL80211_RATE_INFO_BITRATE)?.get(..2)?.try_into().ok()?) as u32,
+        };
+        Some(bitrate as f64 / 10.0).filter(|bitrate| *bitrate > 0.0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_with_updated_marks() {
+        let link = parse_line(" wlp3s0: 0000   54.  -56.  -256        0      0      0      0     12        0").unwrap();
+        assert_eq!(link.interface, "wlp3s0");
+        assert_eq!(link.quality, 54.0);
+        assert_eq!(link.signal, -56.0);
+        assert_eq!(link.noise, -256.0);
+    }
+
+    #[test]
+    fn parse_line_hex_status_and_plain_values() {
+        let link = parse_line("  wlan0: 00a0   70   -40   -95        0      0      0      0      0        0").unwrap();
+        assert_eq!(link.interface, "wlan0");
+        assert_eq!((link.quality, link.signal, link.noise), (70.0, -40.0, -95.0));
+    }
+
+    #[test]
+    fn parse_line_rejects_headers_and_short_lines() {
+        assert!(parse_line("Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE").is_none());
+        assert!(parse_line(" face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22").is_none());
+        assert!(parse_line(" wlan0: 0000   54.").is_none());
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+//Rates kept by window:N at most, 10 minutes at one tick per second
+const MAX_WINDOW: usize = 600;
+
+//How rates are smoothed before display, see rates.smoothing in Config
+//  none        raw rate of the last tick
+//  window:N    mean of the last N rates, N capped to MAX_WINDOW
+//  ewma:S      exponential moving average, a sample weighs half after S seconds
+#[derive(Clone, Copy, PartialEq)]
+pub enum Smoothing {
+    None,
+    Window(usize),
+    Ewma { half_life: f64 },
+}
+
+impl FromStr for Smoothing {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        let value = value.trim().to_lowercase();
+        let (kind, parameter) = value.split_once(':').unwrap_or((&value, ""));
+        match kind.trim() {
+            "none" => Ok(Smoothing::None),
+            "window" => match parameter.trim().parse::<usize>() {
+                Ok(size) if size > 0 => Ok(Smoothing::Window(size.min(MAX_WINDOW))),
+                _ => Err(()),
+            },
+            "ewma" => match parameter.trim().trim_end_matches('s').parse::<f64>() {
+                Ok(half_life) if half_life > 0.0 => Ok(Smoothing::Ewma { half_life }),
+                _ => Err(()),
+            },
+            _ => Err(()),
+        }
+    }
+}
+
+//Smoothed value and peak of one rate
+pub struct Smoother {
+    smoothing: Smoothing,
+    //Last rates, Window only
+    samples: VecDeque<f64>,
+    value: Option<f64>,
+    //Highest smoothed rate seen, a single spiky tick doesn't count for more than it shows
+    peak: f64,
+}
+
+impl Smoother {
+    pub fn new(smoothing: Smoothing) -> Self {
+        Smoother {
+            smoothing,
+            samples: VecDeque::new(),
+            value: None,
+            peak: 0.0,
+        }
+    }
+
+    //Feed the rate measured over the last `elapsed` seconds, returns the smoothed rate
+    pub fn update(&mut self, rate: f64, elapsed: f64) -> f64 {
+        let value = match self.smoothing {
+            Smoothing::None => rate,
+            Smoothing::Window(size) => {
+                self.samples.push_back(rate);
+                while self.samples.len() > size {
+                    self.samples.pop_front();
+                }
+                self.samples.iter().sum::<f64>() / self.samples.len() as f64
+            }
+            Smoothing::Ewma { half_life } => match self.value {
+                //Weight of the new sample grows with the time it covers
+                Some(last) => {
+                    let alpha = 1.0 - 0.5f64.powf(elapsed / half_life);
+                    last + alpha * (rate - last)
+                }
+                None => rate,
+            },
+        };
+        self.value = Some(value);
+        self.peak = self.peak.max(value);
+        value
+    }
+
+    //Last smoothed rate, 0 before the first update
+    pub fn value(&self) -> f64 {
+        self.value.unwrap_or(0.0)
+    }
+
+    pub fn peak(&self) -> f64 {
+        self.peak
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_none() {
+        assert!("none".parse::<Smoothing>() == Ok(Smoothing::None));
+        assert!(" None ".parse::<Smoothing>() == Ok(Smoothing::None));
+    }
+
+    #[test]
+    fn parse_window() {
+        assert!("window:3".parse::<Smoothing>() == Ok(Smoothing::Window(3)));
+        assert!("window:0".parse::<Smoothing>().is_err());
+        assert!("window:-1".parse::<Smoothing>().is_err());
+        assert!("window".parse::<Smoothing>().is_err());
+        assert!("window:100000".parse::<Smoothing>() == Ok(Smoothing::Window(MAX_WINDOW)));
+    }
+
+    #[test]
+    fn parse_ewma() {
+        assert!("ewma:5s".parse::<Smoothing>() == Ok(Smoothing::Ewma { half_life: 5.0 }));
+        assert!("ewma:2.5".parse::<Smoothing>() == Ok(Smoothing::Ewma { half_life: 2.5 }));
+        assert!("ewma:0".parse::<Smoothing>().is_err());
+        assert!("unknown:1".parse::<Smoothing>().is_err());
+    }
+
+    #[test]
+    fn ewma_step_halves_after_half_life() {
+        let mut smoother = Smoother::new(Smoothing::Ewma { half_life: 5.0 });
+        assert_eq!(smoother.update(100.0, 1.0), 100.0);
+        //One half-life later the new sample weighs half
+        assert_eq!(smoother.update(0.0, 5.0), 50.0);
+        assert_eq!(smoother.value(), 50.0);
+    }
+
+    #[test]
+    fn window_mean_and_smoothed_peak() {
+        let mut smoother = Smoother::new(Smoothing::Window(2));
+        assert_eq!(smoother.update(10.0, 1.0), 10.0);
+        assert_eq!(smoother.update(30.0, 1.0), 20.0);
+        assert_eq!(smoother.update(50.0, 1.0), 40.0);
+        assert_eq!(smoother.update(0.0, 1.0), 25.0);
+        assert_eq!(smoother.peak(), 40.0);
+    }
+}
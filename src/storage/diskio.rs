@@ -0,0 +1,188 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::smoothing::{Smoother, Smoothing};
+
+//diskstats always counts 512-byte sectors, whatever the device block size
+const SECTOR_SIZE: u64 = 512;
+//Rate samples kept per device for the charts
+const HISTORY_LEN: usize = 120;
+
+///Block device I/O snapshot
+pub struct DiskIoData {
+    //Kernel name, ex: nvme0n1, dm-0
+    pub device: String,
+    //Friendlier name when there is one, ex: the device-mapper name
+    pub label: String,
+    //Bytes per second, smoothed
+    pub read_rate: f64,
+    pub write_rate: f64,
+    //Highest smoothed rates since Rusys started
+    pub read_peak: f64,
+    pub write_peak: f64,
+    //Completed requests per second
+    pub read_iops: f64,
+    pub write_iops: f64,
+    //Average milliseconds per request, queueing included
+    pub await_ms: f64,
+    //Average requests in flight
+    pub queue_depth: f64,
+    //Percent of the time the device had I/O in flight
+    pub utilization: f64,
+}
+
+//Cumulative counters of one /proc/diskstats line
+#[derive(Clone, Copy)]
+struct DiskCounters {
+    reads: u64,
+    sectors_read: u64,
+    read_ms: u64,
+    writes: u64,
+    sectors_written: u64,
+    write_ms: u64,
+    io_ms: u64,
+    weighted_io_ms: u64,
+}
+
+//Block device statistics
+pub struct DiskIoMonitor {
+    //Last update; collect metrics
+    last_update: Instant,
+    //-> String == device
+    last_counters: HashMap<String, DiskCounters>,
+    //History -> String == device, read and write rates
+    history: HashMap<String, VecDeque<(f64, f64)>>,
+    //-> String == device, read and write smoothing, see rates.smoothing
+    smoothers: HashMap<String, (Smoother, Smoother)>,
+    smoothing: Smoothing,
+}
+
+impl DiskIoMonitor {
+    pub fn new(smoothing: Smoothing) -> Self {
+        DiskIoMonitor {
+            last_update: Instant::now(),
+            last_counters: HashMap::new(),
+            history: HashMap::new(),
+            smoothers: HashMap::new(),
+            smoothing,
+        }
+    }
+
+    //Oldest first, empty for unknown devices
+    pub fn history(&self, device: &str) -> impl DoubleEndedIterator<Item = &(f64, f64)> {
+        self.history.get(device).into_iter().flatten()
+    }
+
+    //Return rates for every whole disk, derived from /proc/diskstats deltas
+    //None when called again too soon, deltas over a tiny interval are noise,
+    //or when /proc/diskstats can't be read: the caller keeps the previous rates
+    pub fn get_disk_io(&mut self) -> Option<Vec<DiskIoData>> {
+        let mut disk_data = Vec::new();
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+
+        if elapsed < 0.1 {
+            return None;
+        }
+        let diskstats = fs::read_to_string("/proc/diskstats").ok()?;
+        let elapsed_ms = elapsed * 1000.0;
+
+        for line in diskstats.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 14 {
+                continue;
+            }
+            let device = fields[2];
+            if !is_disk(device) {
+                continue;
+            }
+            let value = |index: usize| fields[index].parse::<u64>().unwrap_or(0);
+            let current = DiskCounters {
+                reads: value(3),
+                sectors_read: value(5),
+                read_ms: value(6),
+                writes: value(7),
+                sectors_written: value(9),
+                write_ms: value(10),
+                io_ms: value(12),
+                weighted_io_ms: value(13),
+            };
+
+            let (read_smoother, write_smoother) = self
+                .smoothers
+                .entry(device.to_string())
+                .or_insert_with(|| (Smoother::new(self.smoothing), Smoother::new(self.smoothing)));
+            let data = match self.last_counters.get(device) {
+                Some(last) => {
+                    let read_rate = (current.sectors_read.saturating_sub(last.sectors_read) * SECTOR_SIZE) as f64 / elapsed;
+                    let write_rate =
+                        (current.sectors_written.saturating_sub(last.sectors_written) * SECTOR_SIZE) as f64 / elapsed;
+                    let reads = current.reads.saturating_sub(last.reads);
+                    let writes = current.writes.saturating_sub(last.writes);
+                    let wait_ms = current.read_ms.saturating_sub(last.read_ms)
+                        + current.write_ms.saturating_sub(last.write_ms);
+                    DiskIoData {
+                        device: device.to_string(),
+                        label: device_label(device),
+                        read_rate: read_smoother.update(read_rate, elapsed),
+                        write_rate: write_smoother.update(write_rate, elapsed),
+                        read_peak: read_smoother.peak(),
+                        write_peak: write_smoother.peak(),
+                        read_iops: reads as f64 / elapsed,
+                        write_iops: writes as f64 / elapsed,
+                        await_ms: if reads + writes > 0 { wait_ms as f64 / (reads + writes) as f64 } else { 0.0 },
+                        queue_depth: current.weighted_io_ms.saturating_sub(last.weighted_io_ms) as f64 / elapsed_ms,
+                        utilization: (current.io_ms.saturating_sub(last.io_ms) as f64 / elapsed_ms * 100.0).min(100.0),
+                    }
+                }
+                None => DiskIoData {
+                    device: device.to_string(),
+                    label: device_label(device),
+                    read_rate: 0.0,
+                    write_rate: 0.0,
+                    read_peak: 0.0,
+                    write_peak: 0.0,
+                    read_iops: 0.0,
+                    write_iops: 0.0,
+                    await_ms: 0.0,
+                    queue_depth: 0.0,
+                    utilization: 0.0,
+                },
+            };
+
+            let history = self.history.entry(device.to_string()).or_default();
+            history.push_back((data.read_rate, data.write_rate));
+            if history.len() > HISTORY_LEN {
+                history.pop_front();
+            }
+
+            self.last_counters.insert(device.to_string(), current);
+            disk_data.push(data);
+        }
+        //Unplugged USB disks, detached loop devices, removed dm targets
+        let present = |device: &String| disk_data.iter().any(|disk| &disk.device == device);
+        self.last_counters.retain(|device, _| present(device));
+        self.history.retain(|device, _| present(device));
+        self.smoothers.retain(|device, _| present(device));
+        //Updated
+        self.last_update = now;
+        Some(disk_data)
+    }
+}
+
+//Whole disks, dm and md devices; partitions, loop and ram disks are skipped
+fn is_disk(device: &str) -> bool {
+    if device.starts_with("loop") || device.starts_with("ram") {
+        return false;
+    }
+    Path::new("/sys/block").join(device).exists()
+}
+
+//dm-0 -> its device-mapper name (vg-root, luks-...), everything else as is
+pub fn device_label(device: &str) -> String {
+    fs::read_to_string(Path::new("/sys/block").join(device).join("dm/name"))
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|_| device.to_string())
+}
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+use std::fs;
+use std::fs::Metadata;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+//Push partial sizes to the UI every this many files
+const PUBLISH_EVERY: u64 = 2_000;
+
+//One child of the scanned directory
+#[derive(Clone)]
+pub struct DirEntry {
+    pub name: String,
+    pub path: PathBuf,
+    //Disk usage in bytes like du, everything below for directories
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+//What the scan thread shares with the UI
+#[derive(Clone, Default)]
+pub struct ScanProgress {
+    pub entries: Vec<DirEntry>,
+    pub files: u64,
+    //Unreadable directories or files
+    pub errors: u64,
+    pub done: bool,
+    pub cancelled: bool,
+}
+
+//Background du-style scan of one directory, staying on its filesystem
+pub struct DirScan {
+    pub root: PathBuf,
+    progress: Arc<Mutex<ScanProgress>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl DirScan {
+    pub fn start(root: PathBuf) -> Self {
+        let progress = Arc::new(Mutex::new(ScanProgress::default()));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let scan = DirScan {
+            root: root.clone(),
+            progress: Arc::clone(&progress),
+            cancel: Arc::clone(&cancel),
+        };
+        thread::spawn(move || scan_root(&root, &progress, &cancel));
+        scan
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    //Copy of the current results, largest first
+    pub fn progress(&self) -> ScanProgress {
+        let mut progress = self.progress.lock().map(|progress| progress.clone()).unwrap_or_default();
+        progress.entries.sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+        progress
+    }
+}
+
+impl Drop for DirScan {
+    //Leaving the directory stops its scan
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+fn scan_root(root: &Path, progress: &Mutex<ScanProgress>, cancel: &AtomicBool) {
+    let Ok(root_meta) = fs::symlink_metadata(root) else {
+        publish(progress, |state| {
+            state.errors += 1;
+            state.done = true;
+        });
+        return;
+    };
+    let device = root_meta.dev();
+
+    //(device, inode) of files with several links, like du each is only counted once
+    let mut linked: HashSet<(u64, u64)> = HashSet::new();
+    let mut entries: Vec<DirEntry> = Vec::new();
+    let mut errors = 0;
+    match fs::read_dir(root) {
+        Ok(children) => {
+            for child in children.flatten() {
+                let Ok(meta) = child.metadata() else {
+                    errors += 1;
+                    continue;
+                };
+                entries.push(DirEntry {
+                    name: child.file_name().to_string_lossy().to_string(),
+                    path: child.path(),
+                    size: disk_usage(&meta, &mut linked),
+                    //Mount points below are shown but not entered
+                    is_dir: meta.is_dir() && meta.dev() == device,
+                });
+            }
+        }
+        Err(_) => errors += 1,
+    }
+    let mut files = entries.iter().filter(|entry| !entry.is_dir).count() as u64;
+    publish(progress, |state| {
+        state.entries = entries.clone();
+        state.files = files;
+        state.errors = errors;
+    });
+
+    //Directories one by one, the UI sees each size grow
+    for index in 0..entries.len() {
+        if !entries[index].is_dir {
+            continue;
+        }
+        let path = entries[index].path.clone();
+        let mut stack = vec![path];
+        while let Some(dir) = stack.pop() {
+            if cancel.load(Ordering::Relaxed) {
+                publish(progress, |state| {
+                    state.entries = entries.clone();
+                    state.cancelled = true;
+                    state.done = true;
+                });
+                return;
+            }
+            let Ok(children) = fs::read_dir(&dir) else {
+                errors += 1;
+                continue;
+            };
+            for child in children.flatten() {
+                let Ok(meta) = child.metadata() else {
+                    errors += 1;
+                    continue;
+                };
+                //Another filesystem mounted below, du -x skips it
+                if meta.dev() != device {
+                    continue;
+                }
+                entries[index].size += disk_usage(&meta, &mut linked);
+                if meta.is_dir() {
+                    stack.push(child.path());
+                } else {
+                    files += 1;
+                    if files % PUBLISH_EVERY == 0 {
+                        publish(progress, |state| {
+                            state.entries = entries.clone();
+                            state.files = files;
+                            state.errors = errors;
+                        });
+                    }
+                }
+            }
+        }
+        publish(progress, |state| {
+            state.entries = entries.clone();
+            state.files = files;
+            state.errors = errors;
+        });
+    }
+    publish(progress, |state| state.done = true);
+}
+
+//Allocated bytes, 0 for another link to a file already counted
+fn disk_usage(meta: &Metadata, linked: &mut HashSet<(u64, u64)>) -> u64 {
+    if !meta.is_dir() && meta.nlink() > 1 && !linked.insert((meta.dev(), meta.ino())) {
+        return 0;
+    }
+    meta.blocks() * 512
+}
+
+fn publish(progress: &Mutex<ScanProgress>, update: impl FnOnce(&mut ScanProgress)) {
+    if let Ok(mut state) = progress.lock() {
+        update(&mut state);
+    }
+}
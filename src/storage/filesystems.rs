@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nix::sys::statvfs::statvfs;
+
+use crate::config::{glob_match, Config};
+
+///Mounted filesystem usage snapshot
+pub struct FilesystemData {
+    //Where it is mounted
+    pub mount_point: String,
+    //Source device, ex: /dev/nvme0n1p2
+    pub device: String,
+    pub fs_type: String,
+    //Bytes
+    pub total: u64,
+    pub used: u64,
+    //Free for unprivileged users, root reserve excluded
+    pub available: u64,
+    //Inodes, 0 total on filesystems without a fixed inode table (btrfs)
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+    //statvfs didn't answer in time (hung network mount), numbers are the last known ones
+    pub stale: bool,
+}
+
+impl FilesystemData {
+    //Like df: used / (used + available), root reserve doesn't count as free
+    pub fn use_percent(&self) -> f64 {
+        let usable = self.used + self.available;
+        if usable == 0 {
+            return 0.0;
+        }
+        self.used as f64 / usable as f64 * 100.0
+    }
+
+    pub fn inode_percent(&self) -> Option<f64> {
+        if self.inodes_total == 0 {
+            return None;
+        }
+        Some(self.inodes_used as f64 / self.inodes_total as f64 * 100.0)
+    }
+}
+
+//Give up waiting for statvfs after this long, the mount is shown as stale
+const STATVFS_TIMEOUT: Duration = Duration::from_millis(100);
+
+//Space and inode counters of one mount
+#[derive(Clone, Copy)]
+struct Usage {
+    total: u64,
+    used: u64,
+    available: u64,
+    inodes_total: u64,
+    inodes_used: u64,
+}
+
+//Thread answering statvfs for one mount point, a hung NFS/CIFS server only blocks it
+struct Prober {
+    request: Sender<()>,
+    answer: Receiver<Option<Usage>>,
+    //Asked and not answered yet
+    busy: bool,
+    //Last answer, shown while the mount is stale
+    last: Option<Usage>,
+}
+
+impl Prober {
+    fn spawn(mount_point: &str) -> Self {
+        let (request, requests) = mpsc::channel::<()>();
+        let (answers, answer) = mpsc::channel();
+        let path = mount_point.to_string();
+        //Ends once the prober is dropped, or never if statvfs never returns
+        thread::spawn(move || {
+            for () in requests {
+                if answers.send(probe(&path)).is_err() {
+                    return;
+                }
+            }
+        });
+        Prober {
+            request,
+            answer,
+            busy: false,
+            last: None,
+        }
+    }
+}
+
+//Usage of every real filesystem from /proc/self/mounts, pseudo ones filtered by the config rules
+pub struct FilesystemMonitor {
+    //-> String == mount point
+    probers: HashMap<String, Prober>,
+}
+
+impl FilesystemMonitor {
+    pub fn new() -> Self {
+        FilesystemMonitor {
+            probers: HashMap::new(),
+        }
+    }
+
+    //Waits STATVFS_TIMEOUT at most, mounts that don't answer come back stale
+    pub fn get_filesystems(&mut self, config: &Config) -> Vec<FilesystemData> {
+        let Ok(mounts) = fs::read_to_string("/proc/self/mounts") else {
+            return Vec::new();
+        };
+
+        //(device, mount point, type)
+        let mut entries: Vec<(String, String, String)> = Vec::new();
+        for line in mounts.lines() {
+            let mut fields = line.split_whitespace();
+            let (Some(device), Some(mount_point), Some(fs_type)) = (fields.next(), fields.next(), fields.next()) else {
+                continue;
+            };
+            let mount_point = unescape(mount_point);
+            if is_excluded(config, &mount_point, fs_type) {
+                continue;
+            }
+            //Something mounted over an earlier entry hides it, keep the latest
+            entries.retain(|(_, mount, _)| mount != &mount_point);
+            entries.push((unescape(device), mount_point, fs_type.to_string()));
+        }
+
+        //Unmounted: a hung prober thread is left to finish on its own
+        self.probers
+            .retain(|mount, _| entries.iter().any(|(_, mount_point, _)| mount_point == mount));
+        //Ask every mount at once, the timeout is shared
+        for (_, mount_point, _) in &entries {
+            let prober = self
+                .probers
+                .entry(mount_point.clone())
+                .or_insert_with(|| Prober::spawn(mount_point));
+            if !prober.busy {
+                prober.busy = prober.request.send(()).is_ok();
+            }
+        }
+        let deadline = Instant::now() + STATVFS_TIMEOUT;
+
+        let mut filesystems = Vec::new();
+        for (device, mount_point, fs_type) in entries {
+            let Some(prober) = self.probers.get_mut(&mount_point) else {
+                continue;
+            };
+            let stale = match prober.answer.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(usage) => {
+                    prober.busy = false;
+                    prober.last = usage;
+                    false
+                }
+                Err(RecvTimeoutError::Timeout) => true,
+                //Thread died, start a new one next time
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.probers.remove(&mount_point);
+                    continue;
+                }
+            };
+            let usage = match (prober.last, stale) {
+                (Some(usage), _) => usage,
+                //Never answered yet, nothing to show but the name
+                (None, true) => Usage {
+                    total: 0,
+                    used: 0,
+                    available: 0,
+                    inodes_total: 0,
+                    inodes_used: 0,
+                },
+                //statvfs failed
+                (None, false) => continue,
+            };
+            if usage.total == 0 && !stale {
+                continue;
+            }
+            filesystems.push(FilesystemData {
+                mount_point,
+                device,
+                fs_type,
+                total: usage.total,
+                used: usage.used,
+                available: usage.available,
+                inodes_total: usage.inodes_total,
+                inodes_used: usage.inodes_used,
+                stale,
+            });
+        }
+        filesystems.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+        filesystems
+    }
+}
+
+//statvfs counters are c_ulong / fsblkcnt_t, only u64 on 64-bit targets
+#[allow(clippy::unnecessary_cast)]
+fn probe(mount_point: &str) -> Option<Usage> {
+    let stat = statvfs(mount_point).ok()?;
+    //Fragment size is the unit of the block counters
+    let block = stat.fragment_size() as u64;
+    let total = stat.blocks() as u64 * block;
+    Some(Usage {
+        total,
+        used: total.saturating_sub(stat.blocks_free() as u64 * block),
+        available: stat.blocks_available() as u64 * block,
+        inodes_total: stat.files() as u64,
+        inodes_used: (stat.files() as u64).saturating_sub(stat.files_free() as u64),
+    })
+}
+
+fn is_excluded(config: &Config, mount_point: &str, fs_type: &str) -> bool {
+    config.fs_exclude_types.iter().any(|pattern| glob_match(pattern, fs_type))
+        || config.fs_exclude_mounts.iter().any(|pattern| glob_match(pattern, mount_point))
+}
+
+//Mount table escapes spaces and friends as \040 octal
+pub fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let digits: String = (0..3).filter_map(|_| chars.next_if(|d| d.is_digit(8))).collect();
+            if let Some(byte) = u8::from_str_radix(&digits, 8).ok().filter(|_| digits.len() == 3) {
+                out.push(byte as char);
+                continue;
+            }
+            out.push(c);
+            out.push_str(&digits);
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn octal_escapes() {
+        assert_eq!(unescape(r"/mnt/My\040Drive"), "/mnt/My Drive");
+        assert_eq!(unescape(r"/mnt/tab\011here"), "/mnt/tab\there");
+        assert_eq!(unescape(r"/mnt/back\134slash"), r"/mnt/back\slash");
+    }
+
+    #[test]
+    fn broken_escapes_are_kept() {
+        assert_eq!(unescape(r"/mnt/a\04"), r"/mnt/a\04");
+        assert_eq!(unescape(r"/mnt/a\x"), r"/mnt/a\x");
+        assert_eq!(unescape("/mnt/plain"), "/mnt/plain");
+    }
+}
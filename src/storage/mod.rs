@@ -0,0 +1,12 @@
+mod diskio;
+pub use self::diskio::{DiskIoData, DiskIoMonitor};
+mod explorer;
+pub use self::explorer::{DirScan, ScanProgress};
+mod filesystems;
+pub use self::filesystems::{FilesystemData, FilesystemMonitor};
+mod nfs;
+pub use self::nfs::{NfsMonitor, NfsMountData};
+mod projection;
+pub use self::projection::UsageTrend;
+mod stack;
+pub use self::stack::{BlockKind, StackMonitor, StackNode};
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::Instant;
+
+use super::filesystems::unescape;
+
+///Per operation type NFS client statistics
+pub struct NfsOpData {
+    //Operation name, ex: READ, GETATTR
+    pub op: String,
+    pub ops_rate: f64,
+    //Average milliseconds per op over the last tick, 0 when idle
+    pub rtt_ms: f64,
+    pub exec_ms: f64,
+    //Requests sent again per second
+    pub retrans_rate: f64,
+}
+
+///NFS mount snapshot
+pub struct NfsMountData {
+    //Export, ex: server:/srv/cache
+    pub export: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub ops_rate: f64,
+    pub retrans_rate: f64,
+    //Busiest first, ops never used since mount are left out
+    pub ops: Vec<NfsOpData>,
+}
+
+//Cumulative counters of one "per-op statistics" line
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct OpCounters {
+    ops: u64,
+    transmissions: u64,
+    rtt_ms: u64,
+    exec_ms: u64,
+}
+
+//One NFS mount of /proc/self/mountstats with its raw counters
+struct MountStats {
+    export: String,
+    mount_point: String,
+    fs_type: String,
+    //-> String == op, ex: READ
+    ops: Vec<(String, OpCounters)>,
+}
+
+//NFS client statistics from /proc/self/mountstats
+pub struct NfsMonitor {
+    //Last update; collect metrics
+    last_update: Instant,
+    //-> (mount point, op) == counters
+    last_counters: HashMap<(String, String), OpCounters>,
+}
+
+impl NfsMonitor {
+    pub fn new() -> Self {
+        NfsMonitor {
+            last_update: Instant::now(),
+            last_counters: HashMap::new(),
+        }
+    }
+
+    //None when called again too soon to compute rates or mountstats can't be read
+    pub fn get_nfs_stats(&mut self) -> Option<Vec<NfsMountData>> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+
+        if elapsed < 0.1 {
+            return None;
+        }
+        //Unreadable: keep the previous table and counters rather than blanking both
+        let mountstats = fs::read_to_string("/proc/self/mountstats").ok()?;
+
+        let mut mounts = Vec::new();
+        for stats in parse_mountstats(&mountstats) {
+            let mut mount = NfsMountData {
+                export: stats.export,
+                mount_point: stats.mount_point,
+                fs_type: stats.fs_type,
+                ops_rate: 0.0,
+                retrans_rate: 0.0,
+                ops: Vec::new(),
+            };
+            for (op, counters) in stats.ops {
+                let key = (mount.mount_point.clone(), op.clone());
+                let data = match self.last_counters.get(&key) {
+                    Some(last) => {
+                        let ops = counters.ops.saturating_sub(last.ops);
+                        let retrans = counters
+                            .transmissions
+                            .saturating_sub(counters.ops)
+                            .saturating_sub(last.transmissions.saturating_sub(last.ops));
+                        let per_op = |now: u64, before: u64| {
+                            if ops > 0 { now.saturating_sub(before) as f64 / ops as f64 } else { 0.0 }
+                        };
+                        NfsOpData {
+                            op,
+                            ops_rate: ops as f64 / elapsed,
+                            rtt_ms: per_op(counters.rtt_ms, last.rtt_ms),
+                            exec_ms: per_op(counters.exec_ms, last.exec_ms),
+                            retrans_rate: retrans as f64 / elapsed,
+                        }
+                    }
+                    None => NfsOpData {
+                        op,
+                        ops_rate: 0.0,
+                        rtt_ms: 0.0,
+                        exec_ms: 0.0,
+                        retrans_rate: 0.0,
+                    },
+                };
+                mount.ops_rate += data.ops_rate;
+                mount.retrans_rate += data.retrans_rate;
+                mount.ops.push(data);
+                self.last_counters.insert(key, counters);
+            }
+            mounts.push(mount);
+        }
+
+        for mount in &mut mounts {
+            mount.ops.sort_by(|a, b| b.ops_rate.total_cmp(&a.ops_rate).then(a.op.cmp(&b.op)));
+        }
+        //Unmounted since, their counters would be stale on a remount
+        self.last_counters
+            .retain(|(mount_point, _), _| mounts.iter().any(|mount| &mount.mount_point == mount_point));
+        //Updated
+        self.last_update = now;
+        Some(mounts)
+    }
+}
+
+//NFS mounts and their per-op counters, other filesystems and ops never used since mount are left out
+fn parse_mountstats(content: &str) -> Vec<MountStats> {
+    let mut mounts = Vec::new();
+    let mut current: Option<MountStats> = None;
+    let mut in_ops = false;
+    for line in content.lines() {
+        //device server:/export mounted on /mnt with fstype nfs4 statvers=1.1
+        if let Some(rest) = line.strip_prefix("device ") {
+            mounts.extend(current.take());
+            in_ops = false;
+            let fields: Vec<&str> = rest.split_whitespace().collect();
+            if let [export, "mounted", "on", mount_point, "with", "fstype", fs_type, ..] = fields[..] {
+                if fs_type.starts_with("nfs") {
+                    current = Some(MountStats {
+                        export: unescape(export),
+                        mount_point: unescape(mount_point),
+                        fs_type: fs_type.to_string(),
+                        ops: Vec::new(),
+                    });
+                }
+            }
+            continue;
+        }
+        let Some(mount) = current.as_mut() else {
+            continue;
+        };
+        let line = line.trim();
+        if line == "per-op statistics" {
+            in_ops = true;
+            continue;
+        }
+        if !in_ops {
+            continue;
+        }
+
+        //READ: ops transmissions timeouts bytes_sent bytes_recv queue_ms rtt_ms exec_ms [errors]
+        let Some((op, values)) = line.split_once(':') else {
+            continue;
+        };
+        let values: Vec<u64> = values.split_whitespace().filter_map(|value| value.parse().ok()).collect();
+        if values.len() < 8 || values[0] == 0 {
+            continue;
+        }
+        mount.ops.push((
+            op.to_string(),
+            OpCounters {
+                ops: values[0],
+                transmissions: values[1],
+                rtt_ms: values[6],
+                exec_ms: values[7],
+            },
+        ));
+    }
+    mounts.extend(current);
+    mounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTSTATS: &str = "\
+device sysfs mounted on /sys with fstype sysfs
+device nas:/srv/media\\040files mounted on /mnt/media\\040files with fstype nfs4 statvers=1.1
+\topts:\trw,vers=4.2,rsize=1048576,wsize=1048576
+\tevents:\t1 2 3 4 5 6 7 8 9 10
+\tbytes:\t100 200 0 0 0 0 0 0
+
+\tper-op statistics
+\t        NULL: 1 1 0 44 24 0 0 0 0
+\t        READ: 120 123 3 18240 52428800 40 960 1200 0
+\t       WRITE: 0 0 0 0 0 0 0 0 0
+\t     GETATTR: 50 50 0 9600 12000 5 100 110
+
+device server:/export mounted on /home with fstype nfs statvers=1.1
+\tper-op statistics
+\t      LOOKUP: 7 7 0 1400 1800 0 21 28 2
+";
+
+    #[test]
+    fn per_op_counters() {
+        let mounts = parse_mountstats(MOUNTSTATS);
+        assert_eq!(mounts.len(), 2);
+        let media = &mounts[0];
+        assert_eq!(media.fs_type, "nfs4");
+        let ops: Vec<&str> = media.ops.iter().map(|(op, _)| op.as_str()).collect();
+        //WRITE never used since mount
+        assert_eq!(ops, vec!["NULL", "READ", "GETATTR"]);
+        assert_eq!(
+            media.ops[1].1,
+            OpCounters {
+                ops: 120,
+                transmissions: 123,
+                rtt_ms: 960,
+                exec_ms: 1200,
+            }
+        );
+        assert_eq!(mounts[1].mount_point, "/home");
+        assert_eq!(mounts[1].ops[0].1.exec_ms, 28);
+    }
+
+    #[test]
+    fn escaped_mount_points() {
+        let mounts = parse_mountstats(MOUNTSTATS);
+        assert_eq!(mounts[0].export, "nas:/srv/media files");
+        assert_eq!(mounts[0].mount_point, "/mnt/media files");
+    }
+
+    #[test]
+    fn short_lines_are_skipped() {
+        let mounts = parse_mountstats(
+            "device a:/b mounted on /c with fstype nfs\n\
+             \tper-op statistics\n\
+             \tREAD: 1 2 3\n",
+        );
+        assert_eq!(mounts.len(), 1);
+        assert!(mounts[0].ops.is_empty());
+    }
+}
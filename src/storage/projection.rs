@@ -0,0 +1,143 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::filesystems::FilesystemData;
+
+//One sample per filesystem at most this often, ticks are much faster than disks fill
+const SAMPLE_EVERY: Duration = Duration::from_secs(10);
+//Fewer points than this, or a shorter span, and the slope is just noise
+const MIN_SAMPLES: usize = 6;
+const MIN_SPAN: Duration = Duration::from_secs(60);
+//Fraction of the filesystem size per hour, slower growth is a flat filesystem with noise
+//(short-lived files, logs rotating), 0.1% == over 41 days to fill it from empty
+const MIN_GROWTH_PER_HOUR: f64 = 0.001;
+
+//Used-space history per mount point, fitted with a least-squares line
+pub struct UsageTrend {
+    //Samples older than this are dropped
+    window: Duration,
+    last_sample: Option<Instant>,
+    //-> String == mount point, (when, used bytes)
+    samples: HashMap<String, VecDeque<(Instant, u64)>>,
+}
+
+impl UsageTrend {
+    pub fn new(window: Duration) -> Self {
+        UsageTrend {
+            window,
+            last_sample: None,
+            samples: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, filesystems: &[FilesystemData]) {
+        let now = Instant::now();
+        if self.last_sample.is_some_and(|last| now.duration_since(last) < SAMPLE_EVERY) {
+            return;
+        }
+        self.last_sample = Some(now);
+
+        for filesystem in filesystems {
+            //Last known numbers repeated, not a measurement
+            if filesystem.stale {
+                continue;
+            }
+            let samples = self.samples.entry(filesystem.mount_point.clone()).or_default();
+            samples.push_back((now, filesystem.used));
+            while samples.front().is_some_and(|(at, _)| now.duration_since(*at) > self.window) {
+                samples.pop_front();
+            }
+        }
+        //Unmounted filesystems
+        self.samples
+            .retain(|mount, _| filesystems.iter().any(|filesystem| &filesystem.mount_point == mount));
+    }
+
+    //Growth in bytes per second, None until there is enough history
+    pub fn growth_rate(&self, mount_point: &str) -> Option<f64> {
+        let samples = self.samples.get(mount_point)?;
+        let (first, _) = samples.front()?;
+        let (last, _) = samples.back()?;
+        if samples.len() < MIN_SAMPLES || last.duration_since(*first) < MIN_SPAN {
+            return None;
+        }
+
+        //Least squares over (seconds since first sample, used bytes)
+        let points: Vec<(f64, f64)> = samples
+            .iter()
+            .map(|(at, used)| (at.duration_since(*first).as_secs_f64(), *used as f64))
+            .collect();
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+        let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        if variance == 0.0 {
+            return None;
+        }
+        Some(covariance / variance)
+    }
+
+    //Time left until available space reaches 0 at the current growth, None when not growing
+    pub fn time_to_full(&self, filesystem: &FilesystemData) -> Option<Duration> {
+        let rate = self.growth_rate(&filesystem.mount_point)?;
+        if rate < filesystem.total as f64 * MIN_GROWTH_PER_HOUR / 3600.0 {
+            return None;
+        }
+        //Still beyond what a Duration holds on huge filesystems
+        Duration::try_from_secs_f64(filesystem.available as f64 / rate).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+    const MIB: u64 = 1024 * 1024;
+
+    fn filesystem(used: u64) -> FilesystemData {
+        FilesystemData {
+            mount_point: "/srv".to_string(),
+            device: "/dev/sda1".to_string(),
+            fs_type: "ext4".to_string(),
+            total: 100 * GIB,
+            used,
+            available: 100 * GIB - used,
+            inodes_total: 0,
+            inodes_used: 0,
+            stale: false,
+        }
+    }
+
+    //One sample every SAMPLE_EVERY, used bytes from the closure
+    fn trend(count: u32, used: impl Fn(u32) -> u64) -> UsageTrend {
+        let mut trend = UsageTrend::new(Duration::from_secs(3600));
+        let start = Instant::now();
+        let samples = (0..count).map(|i| (start + SAMPLE_EVERY * i, used(i))).collect();
+        trend.samples.insert("/srv".to_string(), samples);
+        trend
+    }
+
+    #[test]
+    fn flat_noisy_series_has_no_projection() {
+        //Temp files coming and going, +-1 MiB around 50 GiB
+        let trend = trend(12, |i| if i % 2 == 0 { 50 * GIB - MIB } else { 50 * GIB + MIB });
+        assert!(trend.growth_rate("/srv").unwrap() > 0.0);
+        assert_eq!(trend.time_to_full(&filesystem(50 * GIB)), None);
+    }
+
+    #[test]
+    fn steady_growth_is_projected() {
+        //10 MiB per sample == 1 MiB/s
+        let trend = trend(12, |i| 50 * GIB + u64::from(i) * 10 * MIB);
+        let left = trend.time_to_full(&filesystem(50 * GIB)).unwrap();
+        assert_eq!(left.as_secs(), 50 * 1024);
+    }
+
+    #[test]
+    fn short_history_has_no_projection() {
+        let trend = trend(MIN_SAMPLES as u32 - 1, |i| 50 * GIB + u64::from(i) * 10 * MIB);
+        assert_eq!(trend.growth_rate("/srv"), None);
+    }
+}
@@ -0,0 +1,364 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::diskio::device_label;
+use super::filesystems::FilesystemData;
+
+//Guard against odd sysfs loops
+const MAX_DEPTH: usize = 16;
+//Walking sysfs and resolving every /dev path is not free, and stacks rarely change
+const REFRESH_EVERY: Duration = Duration::from_secs(5);
+
+#[derive(Clone, PartialEq)]
+pub enum BlockKind {
+    Disk,
+    Partition,
+    Lvm,
+    Crypt,
+    //Multipath, thin pools, anything else device-mapper
+    DeviceMapper,
+    //md array, with its level (raid1, raid5...)
+    Raid(String),
+    Loop,
+}
+
+impl BlockKind {
+    pub fn label(&self) -> String {
+        match self {
+            BlockKind::Disk => "disk".to_string(),
+            BlockKind::Partition => "part".to_string(),
+            BlockKind::Lvm => "lvm".to_string(),
+            BlockKind::Crypt => "crypt".to_string(),
+            BlockKind::DeviceMapper => "dm".to_string(),
+            BlockKind::Raid(level) => level.clone(),
+            BlockKind::Loop => "loop".to_string(),
+        }
+    }
+}
+
+//md array state from /proc/mdstat
+pub struct RaidStatus {
+    //Member map, ex: [UU_]
+    pub members: String,
+    //A member is missing or failed
+    pub degraded: bool,
+    //Running resync/recovery/reshape/check, ex: "recovery 8.5% finish=0.7min"
+    pub sync: Option<String>,
+}
+
+//One device of the tree, top-level devices first and their backing devices below
+pub struct StackNode {
+    //Kernel name, ex: dm-0
+    pub device: String,
+    //Friendlier name, ex: vg-root
+    pub label: String,
+    pub kind: BlockKind,
+    //Bytes
+    pub size: u64,
+    //Mount points or [SWAP], comma separated
+    pub mount: Option<String>,
+    pub raid: Option<RaidStatus>,
+    //Tree guides to draw in front of the name, ex: "│  └─"
+    pub prefix: String,
+}
+
+//Storage tree, rebuilt every REFRESH_EVERY
+pub struct StackMonitor {
+    last_refresh: Option<Instant>,
+}
+
+impl StackMonitor {
+    pub fn new() -> Self {
+        StackMonitor { last_refresh: None }
+    }
+
+    //None when the last tree is recent enough to keep
+    pub fn get_storage_stack(&mut self, filesystems: &[FilesystemData]) -> Option<Vec<StackNode>> {
+        let now = Instant::now();
+        if self.last_refresh.is_some_and(|last| now.duration_since(last) < REFRESH_EVERY) {
+            return None;
+        }
+        self.last_refresh = Some(now);
+        Some(build_stack(filesystems))
+    }
+}
+
+//Walk from every device nobody sits on top of down to the physical disks
+fn build_stack(filesystems: &[FilesystemData]) -> Vec<StackNode> {
+    let devices = block_devices();
+    let mounts = mount_map(filesystems);
+    let mdstat = fs::read_to_string("/proc/mdstat").unwrap_or_default();
+
+    let mut tops: Vec<&String> = devices
+        .iter()
+        .filter(|device| uppers(device).is_empty())
+        //Unused loop and ram disks are just noise
+        .filter(|device| {
+            !(device.starts_with("loop") || device.starts_with("ram")) || mounts.iter().any(|(dev, _)| dev == *device)
+        })
+        .collect();
+    tops.sort();
+
+    let mut walk = Walk {
+        mounts: &mounts,
+        mdstat: &mdstat,
+        seen: HashSet::new(),
+        nodes: Vec::new(),
+    };
+    for (i, top) in tops.iter().enumerate() {
+        walk.seen.clear();
+        walk.visit(top, "", i + 1 == tops.len(), 0);
+    }
+    walk.nodes
+}
+
+//What a walk down one stack shares between devices
+struct Walk<'a> {
+    mounts: &'a [(String, String)],
+    mdstat: &'a str,
+    //Devices of the current tree, a device only shows up once per tree
+    seen: HashSet<String>,
+    nodes: Vec<StackNode>,
+}
+
+impl Walk<'_> {
+    fn visit(&mut self, device: &str, indent: &str, last: bool, depth: usize) {
+        if depth > MAX_DEPTH || !self.seen.insert(device.to_string()) {
+            return;
+        }
+        let class = class_path(device);
+        let kind = block_kind(device);
+        let raid = match kind {
+            BlockKind::Raid(_) => raid_status(self.mdstat, device),
+            _ => None,
+        };
+        let prefix = match depth {
+            0 => String::new(),
+            _ => format!("{}{}", indent, if last { "└─" } else { "├─" }),
+        };
+        self.nodes.push(StackNode {
+            device: device.to_string(),
+            label: device_label(device),
+            kind,
+            size: read_u64(&class.join("size")).unwrap_or(0) * 512,
+            mount: Some(
+                self.mounts
+                    .iter()
+                    .filter(|(dev, _)| dev == device)
+                    .map(|(_, mount)| mount.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+            .filter(|mount| !mount.is_empty()),
+            raid,
+            prefix,
+        });
+
+        let child_indent = match depth {
+            0 => String::new(),
+            _ => format!("{}{}", indent, if last { "  " } else { "│ " }),
+        };
+        let mut lowers = lowers(device);
+        lowers.sort();
+        for (i, lower) in lowers.iter().enumerate() {
+            self.visit(lower, &child_indent, i + 1 == lowers.len(), depth + 1);
+        }
+    }
+}
+
+//Every block device including partitions
+fn block_devices() -> Vec<String> {
+    fs::read_dir("/sys/class/block")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn class_path(device: &str) -> PathBuf {
+    Path::new("/sys/class/block").join(device)
+}
+
+fn list_dir(path: &Path) -> Vec<String> {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+//Disk a partition belongs to, its sysfs directory sits inside the disk one
+fn partition_parent(device: &str) -> Option<String> {
+    let class = class_path(device);
+    if !class.join("partition").exists() {
+        return None;
+    }
+    let real = fs::canonicalize(&class).ok()?;
+    Some(real.parent()?.file_name()?.to_string_lossy().to_string())
+}
+
+//Devices below: dm/md slaves, or the disk of a partition
+fn lowers(device: &str) -> Vec<String> {
+    let mut lowers = list_dir(&class_path(device).join("slaves"));
+    lowers.extend(partition_parent(device));
+    lowers
+}
+
+//Devices on top: dm/md holders and partitions
+fn uppers(device: &str) -> Vec<String> {
+    let class = class_path(device);
+    let mut uppers = list_dir(&class.join("holders"));
+    uppers.extend(
+        list_dir(&class)
+            .into_iter()
+            .filter(|entry| entry.starts_with(device) && class.join(entry).join("partition").exists()),
+    );
+    uppers
+}
+
+fn block_kind(device: &str) -> BlockKind {
+    let class = class_path(device);
+    if class.join("partition").exists() {
+        return BlockKind::Partition;
+    }
+    if let Ok(level) = fs::read_to_string(class.join("md/level")) {
+        return BlockKind::Raid(level.trim().to_string());
+    }
+    //dm uuid prefix tells who created the mapping
+    if let Ok(uuid) = fs::read_to_string(class.join("dm/uuid")) {
+        return match uuid.trim() {
+            uuid if uuid.starts_with("LVM-") => BlockKind::Lvm,
+            uuid if uuid.starts_with("CRYPT-") => BlockKind::Crypt,
+            _ => BlockKind::DeviceMapper,
+        };
+    }
+    if device.starts_with("loop") {
+        return BlockKind::Loop;
+    }
+    BlockKind::Disk
+}
+
+//-> (kernel device name, mount point), swap devices included as [SWAP]
+fn mount_map(filesystems: &[FilesystemData]) -> Vec<(String, String)> {
+    let mut mounts: Vec<(String, String)> = filesystems
+        .iter()
+        .filter_map(|filesystem| Some((kernel_name(&filesystem.device)?, filesystem.mount_point.clone())))
+        .collect();
+    let swaps = fs::read_to_string("/proc/swaps").unwrap_or_default();
+    for line in swaps.lines().skip(1) {
+        if let Some(device) = line.split_whitespace().next().and_then(kernel_name) {
+            mounts.push((device, "[SWAP]".to_string()));
+        }
+    }
+    mounts
+}
+
+//"/dev/mapper/vg-root" -> "dm-0", following the symlink
+fn kernel_name(source: &str) -> Option<String> {
+    if !source.starts_with("/dev/") {
+        return None;
+    }
+    let real = fs::canonicalize(source).ok()?;
+    Some(real.file_name()?.to_string_lossy().to_string())
+}
+
+//md0 : active raid1 sdb1[1] sda1[0]
+//      1046528 blocks super 1.2 [2/1] [U_]
+//      [=>...................]  recovery =  8.5% (89600/1046528) finish=0.7min speed=22400K/sec
+fn raid_status(mdstat: &str, device: &str) -> Option<RaidStatus> {
+    let mut lines = mdstat.lines().skip_while(|line| !line.starts_with(&format!("{} :", device)));
+    lines.next()?;
+    let mut status = RaidStatus {
+        members: String::new(),
+        degraded: false,
+        sync: None,
+    };
+    //The block ends at the next empty line
+    for line in lines.take_while(|line| !line.trim().is_empty()) {
+        if let Some(members) = line.split_whitespace().last().filter(|last| last.starts_with('[') && last.ends_with(']')) {
+            if members.chars().all(|c| matches!(c, '[' | ']' | 'U' | '_')) {
+                status.members = members.to_string();
+                status.degraded = members.contains('_');
+            }
+        }
+        for action in ["resync", "recovery", "reshape", "check"] {
+            if let Some(rest) = line.split_once(&format!("{} =", action)).map(|(_, rest)| rest) {
+                let mut fields = rest.split_whitespace();
+                let percent = fields.next().unwrap_or("");
+                let finish = fields.find(|field| field.starts_with("finish=")).unwrap_or("");
+                status.sync = Some(format!("{} {} {}", action, percent, finish).trim_end().to_string());
+            }
+        }
+        if line.contains("resync=DELAYED") || line.contains("resync=PENDING") {
+            status.sync = Some("resync pending".to_string());
+        }
+    }
+    Some(status)
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MDSTAT: &str = "\
+Personalities : [raid1] [raid6] [raid5] [raid4]
+md0 : active raid1 sdb1[1] sda1[0]
+      1046528 blocks super 1.2 [2/2] [UU]
+
+md1 : active raid5 sde1[3] sdd1[1] sdc1[0]
+      2093056 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]
+      [=>...................]  recovery =  8.5% (89600/1046528) finish=0.7min speed=22400K/sec
+
+md2 : active raid1 sdg1[1] sdf1[0]
+      1046528 blocks super 1.2 [2/2] [UU]
+        resync=DELAYED
+
+md10 : active raid1 sdi1[1] sdh1[0]
+      1046528 blocks super 1.2 [2/2] [UU]
+      [==>..................]  check = 12.0% (125952/1046528) finish=1.2min speed=12595K/sec
+
+unused devices: <none>
+";
+
+    #[test]
+    fn raid_clean() {
+        let status = raid_status(MDSTAT, "md0").unwrap();
+        assert_eq!(status.members, "[UU]");
+        assert!(!status.degraded);
+        assert!(status.sync.is_none());
+    }
+
+    #[test]
+    fn raid_degraded_recovering() {
+        let status = raid_status(MDSTAT, "md1").unwrap();
+        assert_eq!(status.members, "[UU_]");
+        assert!(status.degraded);
+        assert_eq!(status.sync.as_deref(), Some("recovery 8.5% finish=0.7min"));
+    }
+
+    #[test]
+    fn raid_pending_resync() {
+        let status = raid_status(MDSTAT, "md2").unwrap();
+        assert_eq!(status.sync.as_deref(), Some("resync pending"));
+    }
+
+    #[test]
+    fn raid_name_prefix_is_not_a_match() {
+        //md1 must not pick up md10
+        let status = raid_status(MDSTAT, "md10").unwrap();
+        assert_eq!(status.sync.as_deref(), Some("check 12.0% finish=1.2min"));
+        assert!(raid_status(MDSTAT, "md3").is_none());
+    }
+}
@@ -1,26 +1,560 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
 use sysinfo::System;
+use crossterm::event::KeyCode;
+use ratatui::widgets::TableState;
+use crate::config::{data_path, Config};
 use crate::monitoring::cpu::CpuMonitor;
-use crate::network::{NetworkData, NetworkMonitor};
+use crate::monitoring::lifecycle::LifecycleMonitor;
+use crate::monitoring::limits::{LimitMonitor, LimitUsage};
+use crate::monitoring::pinned::{PinMonitor, PinPattern};
+use crate::monitoring::problems::{Problem, ProblemDetector};
+use crate::monitoring::process::{get_process_info, ProcessInfo};
+use crate::monitoring::systemd::{UnitInfo, UnitMonitor};
+use crate::network::{
+    get_dns_config, get_neighbors, get_routes, Accounting, DnsConfig, InterfaceKind, Neighbor, NetworkData,
+    NetworkMonitor, Period, ProtocolMonitor, ProtocolRate, Route, SocketInfo, SocketMonitor, WirelessData, WirelessMonitor,
+    QUOTA_WARN_RATIO,
+};
+use crate::storage::{
+    DirScan, DiskIoData, DiskIoMonitor, FilesystemData, FilesystemMonitor, NfsMonitor, NfsMountData, ScanProgress,
+    StackMonitor, StackNode, UsageTrend,
+};
+
+//Tabs shown on top of the screen
+#[derive(Clone, Copy, PartialEq)]
+pub enum View {
+    Overview,
+    Network,
+    Connections,
+    Listening,
+    Traffic,
+    Processes,
+    Units,
+    Events,
+    Problems,
+    Pinned,
+    Disks,
+    Storage,
+    Explorer,
+}
+
+impl View {
+    pub const ALL: [View; 13] = [
+        View::Overview,
+        View::Network,
+        View::Connections,
+        View::Listening,
+        View::Traffic,
+        View::Processes,
+        View::Units,
+        View::Events,
+        View::Problems,
+        View::Pinned,
+        View::Disks,
+        View::Storage,
+        View::Explorer,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            View::Overview => "Overview",
+            View::Network => "Network",
+            View::Connections => "Connections",
+            View::Listening => "Listening",
+            View::Traffic => "Traffic",
+            View::Processes => "Processes",
+            View::Units => "Units",
+            View::Events => "Events",
+            View::Problems => "Problems",
+            View::Pinned => "Pinned",
+            View::Disks => "Disks",
+            View::Storage => "Storage",
+            View::Explorer => "Explorer",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        View::ALL.iter().position(|view| view == self).unwrap_or(0)
+    }
+}
 
 pub struct App {
+    pub config: Config,
     pub sys: System,
     pub cpu_monitor: CpuMonitor,
     pub network_monitor: NetworkMonitor,
     pub network_data: Vec<NetworkData>,
+    //Saved on drop, totals survive the exit
+    pub accounting: Accounting,
+    pub wireless_monitor: WirelessMonitor,
+    pub wireless: Vec<WirelessData>,
+    pub protocol_monitor: ProtocolMonitor,
+    pub protocol_health: Vec<ProtocolRate>,
+    pub socket_monitor: SocketMonitor,
+    //Only refreshed while a socket view is shown
+    pub sockets: Vec<SocketInfo>,
+    //Only refreshed while the network view is shown
+    pub routes: Vec<Route>,
+    pub neighbors: Vec<Neighbor>,
+    pub dns: DnsConfig,
+    pub process_data: Vec<ProcessInfo>,
+    pub unit_monitor: UnitMonitor,
+    pub unit_data: Vec<UnitInfo>,
+    pub lifecycle_monitor: LifecycleMonitor,
+    pub problem_detector: ProblemDetector,
+    pub problems: Vec<Problem>,
+    pub limit_monitor: LimitMonitor,
+    pub limit_usage: Vec<LimitUsage>,
+    pub pin_monitor: PinMonitor,
+    pub filesystem_monitor: FilesystemMonitor,
+    pub filesystems: Vec<FilesystemData>,
+    pub usage_trend: UsageTrend,
+    pub disk_io_monitor: DiskIoMonitor,
+    pub disk_io: Vec<DiskIoData>,
+    pub stack_monitor: StackMonitor,
+    pub storage_stack: Vec<StackNode>,
+    pub nfs_monitor: NfsMonitor,
+    pub nfs_mounts: Vec<NfsMountData>,
+    //Directory scan started from the filesystems panel
+    pub explorer: Option<DirScan>,
+    //Mount point the explorer can't go above
+    pub explorer_mount: PathBuf,
+    //Scan results as drawn, taken once per tick so keys act on the rows on screen
+    pub explorer_progress: ScanProgress,
+    pub view: View,
+    pub socket_state: TableState,
+    pub listening_state: TableState,
+    pub routing_state: TableState,
+    pub traffic_state: TableState,
+    pub process_state: TableState,
+    pub unit_state: TableState,
+    pub event_state: TableState,
+    pub problem_state: TableState,
+    pub pin_state: TableState,
+    pub filesystem_state: TableState,
+    pub storage_state: TableState,
+    pub explorer_state: TableState,
+    //Unit picked in the units view, process table only shows its processes
+    pub unit_filter: Option<String>,
+    //Command-line pattern being typed in the pinned view
+    pub pin_input: Option<String>,
+    //Filter being typed in the connections view
+    pub socket_input: Option<String>,
+    //Space separated terms, see socket_matches
+    pub socket_filter: String,
+    //fe80::/169.254 addresses in the network panel
+    pub show_link_local: bool,
+    //Ignore the network.include / network.exclude rules
+    pub show_all_interfaces: bool,
 }
 
 impl App {
     pub fn new() -> Self {
+        let config = Config::load();
+        //Built from the config before it moves into App
+        let network_monitor = NetworkMonitor::new(&config);
+        let accounting = Accounting::load(config.net_accounting.then(|| data_path("traffic.tsv")).flatten());
+        let wireless_monitor = WirelessMonitor::new(config.net_history_length);
+        let protocol_monitor = ProtocolMonitor::new(config.net_history_length);
+        let problem_detector = ProblemDetector::new(&config);
+        let disk_io_monitor = DiskIoMonitor::new(config.rate_smoothing);
+        let lifecycle_monitor = LifecycleMonitor::new(Duration::from_millis(config.events_poll_ms));
         App {
+            usage_trend: UsageTrend::new(Duration::from_secs(config.fs_trend_minutes.saturating_mul(60))),
+            config,
             sys: System::new_all(),
             cpu_monitor: CpuMonitor::new(),
-            network_monitor: NetworkMonitor::new(),
+            network_monitor,
             network_data: Vec::new(),
+            accounting,
+            wireless_monitor,
+            wireless: Vec::new(),
+            protocol_monitor,
+            protocol_health: Vec::new(),
+            socket_monitor: SocketMonitor::new(),
+            sockets: Vec::new(),
+            routes: Vec::new(),
+            neighbors: Vec::new(),
+            dns: DnsConfig::default(),
+            process_data: Vec::new(),
+            unit_monitor: UnitMonitor::new(),
+            unit_data: Vec::new(),
+            lifecycle_monitor,
+            problem_detector,
+            problems: Vec::new(),
+            limit_monitor: LimitMonitor::new(),
+            limit_usage: Vec::new(),
+            pin_monitor: PinMonitor::new(),
+            filesystem_monitor: FilesystemMonitor::new(),
+            filesystems: Vec::new(),
+            disk_io_monitor,
+            disk_io: Vec::new(),
+            stack_monitor: StackMonitor::new(),
+            storage_stack: Vec::new(),
+            nfs_monitor: NfsMonitor::new(),
+            nfs_mounts: Vec::new(),
+            explorer: None,
+            explorer_mount: PathBuf::new(),
+            explorer_progress: ScanProgress::default(),
+            view: View::Overview,
+            socket_state: TableState::default(),
+            listening_state: TableState::default(),
+            routing_state: TableState::default(),
+            traffic_state: TableState::default(),
+            process_state: TableState::default(),
+            unit_state: TableState::default(),
+            event_state: TableState::default(),
+            problem_state: TableState::default(),
+            pin_state: TableState::default(),
+            filesystem_state: TableState::default(),
+            storage_state: TableState::default(),
+            explorer_state: TableState::default(),
+            unit_filter: None,
+            pin_input: None,
+            socket_input: None,
+            socket_filter: String::new(),
+            show_link_local: false,
+            show_all_interfaces: false,
         }
     }
 
     pub fn tick(&mut self) {
         self.sys.refresh_all();
-        self.network_data = self.network_monitor.get_network_info();
+        if let Some(network_data) = self.network_monitor.get_network_info() {
+            self.network_data = network_data;
+            self.accounting.record(&self.network_data);
+        }
+        self.wireless = self.wireless_monitor.get_wireless_info();
+        if let Some(protocol_health) = self.protocol_monitor.get_protocol_health() {
+            self.protocol_health = protocol_health;
+        }
+        self.process_data = get_process_info(&self.sys);
+        if matches!(self.view, View::Connections | View::Listening) {
+            self.sockets = self.socket_monitor.get_sockets();
+        }
+        if self.view == View::Network {
+            self.routes = get_routes();
+            self.neighbors = get_neighbors();
+            self.dns = get_dns_config();
+        }
+        //Events are listed newest first, keep the cursor on the same event
+        let added = self.lifecycle_monitor.update();
+        if let Some(selected) = self.event_state.selected() {
+            let last = self.lifecycle_monitor.len().saturating_sub(1);
+            self.event_state.select(Some((selected + added).min(last)));
+        }
+        self.problems = self.problem_detector.update(&self.process_data);
+        if let Some(limit_usage) = self.limit_monitor.get_limit_usage(&self.process_data) {
+            self.limit_usage = limit_usage;
+        }
+        self.pin_monitor.update(&self.process_data);
+        self.filesystems = self.filesystem_monitor.get_filesystems(&self.config);
+        self.usage_trend.record(&self.filesystems);
+        //None when this tick came too soon after the last one, keep the previous rates
+        if let Some(disk_io) = self.disk_io_monitor.get_disk_io() {
+            self.disk_io = disk_io;
+        }
+        if let Some(nfs_mounts) = self.nfs_monitor.get_nfs_stats() {
+            self.nfs_mounts = nfs_mounts;
+        }
+        //None while the last tree is recent, stacks rarely change
+        if let Some(storage_stack) = self.stack_monitor.get_storage_stack(&self.filesystems) {
+            self.storage_stack = storage_stack;
+        }
+        self.unit_data = self.unit_monitor.get_unit_info();
+        if let Some(scan) = &self.explorer {
+            self.explorer_progress = scan.progress();
+        }
+    }
+
+    //Problems plus limits about to run out, shown next to the tab title
+    pub fn warning_count(&self) -> usize {
+        self.problems.len() + self.limit_usage.iter().filter(|usage| usage.is_warning()).count()
+    }
+
+    //Interfaces past the warning share of network.monthly_quota_gb this month
+    pub fn quota_warnings(&self) -> usize {
+        let quota = self.config.net_monthly_quota_gb * 1_000_000_000.0;
+        if quota <= 0.0 {
+            return 0;
+        }
+        self.visible_interfaces()
+            .iter()
+            .filter(|network| {
+                let month = self.accounting.current(&network.interface, Period::Month);
+                month.total() as f64 / quota >= QUOTA_WARN_RATIO
+            })
+            .count()
+    }
+
+    //Interfaces shown in the network panel, after the include/exclude rules
+    pub fn visible_interfaces(&self) -> Vec<&NetworkData> {
+        self.network_data
+            .iter()
+            .filter(|network| self.show_all_interfaces || !network.hidden)
+            .collect()
+    }
+
+    //Interfaces the traffic accounting counts, see Accounting::record
+    pub fn accounted_interfaces(&self) -> Vec<&NetworkData> {
+        self.network_data
+            .iter()
+            .filter(|network| !network.hidden && !matches!(network.kind, InterfaceKind::Loopback | InterfaceKind::Bridge))
+            .collect()
+    }
+
+    //Sockets shown in the connections view with their process name, after the filter
+    pub fn visible_sockets(&self) -> Vec<(&SocketInfo, Option<&str>)> {
+        let names: HashMap<u32, &str> = self
+            .process_data
+            .iter()
+            .map(|process| (process.pid, process.name.as_str()))
+            .collect();
+        let terms: Vec<&str> = self.socket_filter.split_whitespace().collect();
+        self.sockets
+            .iter()
+            .map(|socket| (socket, socket.pid.and_then(|pid| names.get(&pid).copied())))
+            .filter(|(socket, process)| terms.iter().all(|term| socket_matches(socket, *process, term)))
+            .collect()
+    }
+
+    //Listening sockets by port, with their process name
+    pub fn listening_sockets(&self) -> Vec<(&SocketInfo, Option<&str>)> {
+        let names: HashMap<u32, &str> = self
+            .process_data
+            .iter()
+            .map(|process| (process.pid, process.name.as_str()))
+            .collect();
+        let mut listening: Vec<_> = self
+            .sockets
+            .iter()
+            .filter(|socket| socket.is_listening())
+            .map(|socket| (socket, socket.pid.and_then(|pid| names.get(&pid).copied())))
+            .collect();
+        listening.sort_by_key(|(socket, _)| (socket.local.port(), socket.protocol as u8));
+        listening
+    }
+
+    //Processes shown in the process table, after the unit filter
+    pub fn visible_processes(&self) -> Vec<&ProcessInfo> {
+        let unit = self
+            .unit_filter
+            .as_ref()
+            .and_then(|name| self.unit_data.iter().find(|unit| &unit.name == name));
+        self.process_data
+            .iter()
+            .filter(|process| match (&self.unit_filter, unit) {
+                (None, _) => true,
+                (Some(_), Some(unit)) => unit.pids.contains(&process.pid),
+                //Unit is gone, nothing belongs to it anymore
+                (Some(_), None) => false,
+            })
+            .collect()
+    }
+
+    //Keys go to the text prompt instead of the shortcuts
+    pub fn is_editing(&self) -> bool {
+        self.pin_input.is_some() || self.socket_input.is_some()
+    }
+
+    pub fn on_key(&mut self, code: KeyCode) {
+        if let Some(input) = &mut self.pin_input {
+            match code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let pattern = input.trim().to_string();
+                    if !pattern.is_empty() {
+                        self.pin_monitor.pin(PinPattern::Command(pattern));
+                    }
+                    self.pin_input = None;
+                }
+                KeyCode::Esc => self.pin_input = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(input) = &mut self.socket_input {
+            match code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    self.socket_filter = input.trim().to_string();
+                    self.socket_input = None;
+                    self.move_selection(0);
+                }
+                KeyCode::Esc => self.socket_input = None,
+                _ => {}
+            }
+            return;
+        }
+
+        match code {
+            KeyCode::Tab => {
+                self.view = View::ALL[(self.view.index() + 1) % View::ALL.len()];
+            }
+            KeyCode::BackTab => {
+                self.view = View::ALL[(self.view.index() + View::ALL.len() - 1) % View::ALL.len()];
+            }
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Enter if self.view == View::Units => {
+                let selected = self.unit_state.selected().and_then(|i| self.unit_data.get(i));
+                if let Some(unit) = selected {
+                    self.unit_filter = Some(unit.name.clone());
+                    self.process_state.select(Some(0));
+                    self.view = View::Processes;
+                }
+            }
+            KeyCode::Enter if self.view == View::Disks => {
+                let selected = self.filesystem_state.selected().and_then(|i| self.filesystems.get(i));
+                if let Some(filesystem) = selected {
+                    self.explorer_mount = PathBuf::from(&filesystem.mount_point);
+                    self.explore(self.explorer_mount.clone());
+                    self.view = View::Explorer;
+                }
+            }
+            KeyCode::Enter if self.view == View::Explorer => {
+                let selected = self
+                    .explorer_state
+                    .selected()
+                    .and_then(|index| self.explorer_progress.entries.get(index))
+                    .filter(|entry| entry.is_dir)
+                    .map(|entry| entry.path.clone());
+                if let Some(path) = selected {
+                    self.explore(path);
+                }
+            }
+            KeyCode::Backspace if self.view == View::Explorer => {
+                let parent = self
+                    .explorer
+                    .as_ref()
+                    .filter(|scan| scan.root != self.explorer_mount)
+                    .and_then(|scan| scan.root.parent().map(PathBuf::from));
+                if let Some(parent) = parent {
+                    self.explore(parent);
+                }
+            }
+            KeyCode::Char('c') | KeyCode::Esc if self.view == View::Explorer => {
+                if let Some(scan) = &self.explorer {
+                    scan.cancel();
+                }
+            }
+            KeyCode::Char('p') if self.view == View::Processes => {
+                let selected = self
+                    .process_state
+                    .selected()
+                    .and_then(|i| self.visible_processes().get(i).map(|process| process.name.clone()));
+                if let Some(name) = selected {
+                    self.pin_monitor.pin(PinPattern::Name(name));
+                }
+            }
+            KeyCode::Char('a') if self.view == View::Pinned => self.pin_input = Some(String::new()),
+            KeyCode::Char('d') if self.view == View::Pinned => {
+                if let Some(index) = self.pin_state.selected() {
+                    self.pin_monitor.unpin(index);
+                    self.move_selection(0);
+                }
+            }
+            KeyCode::Char('l') if matches!(self.view, View::Overview | View::Network) => {
+                self.show_link_local = !self.show_link_local;
+            }
+            KeyCode::Char('a') if matches!(self.view, View::Overview | View::Network) => {
+                self.show_all_interfaces = !self.show_all_interfaces;
+            }
+            KeyCode::Char('/') if self.view == View::Connections => {
+                self.socket_input = Some(self.socket_filter.clone());
+            }
+            KeyCode::Esc if self.view == View::Connections => self.socket_filter.clear(),
+            KeyCode::Esc => self.unit_filter = None,
+            _ => {}
+        }
+    }
+
+    //Replaces the running scan, the old one stops when dropped
+    fn explore(&mut self, root: PathBuf) {
+        self.explorer = Some(DirScan::start(root));
+        self.explorer_progress = ScanProgress::default();
+        self.explorer_state.select(Some(0));
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        match self.view {
+            View::Processes => {
+                let len = self.visible_processes().len();
+                step(&mut self.process_state, len, delta);
+            }
+            View::Connections => {
+                let len = self.visible_sockets().len();
+                step(&mut self.socket_state, len, delta);
+            }
+            View::Listening => {
+                let len = self.listening_sockets().len();
+                step(&mut self.listening_state, len, delta);
+            }
+            View::Traffic => {
+                let len = self.accounted_interfaces().len();
+                step(&mut self.traffic_state, len, delta);
+            }
+            View::Units => step(&mut self.unit_state, self.unit_data.len(), delta),
+            View::Events => step(&mut self.event_state, self.lifecycle_monitor.len(), delta),
+            View::Problems => step(&mut self.problem_state, self.problems.len(), delta),
+            View::Pinned => step(&mut self.pin_state, self.pin_monitor.pins.len(), delta),
+            View::Disks => step(&mut self.filesystem_state, self.filesystems.len(), delta),
+            View::Storage => step(&mut self.storage_state, self.storage_stack.len(), delta),
+            View::Explorer => {
+                let len = self.explorer_progress.entries.len();
+                //Cursor hidden by an empty listing comes back on the first row
+                if self.explorer_state.selected().is_none() && len > 0 {
+                    self.explorer_state.select(Some(0));
+                } else {
+                    step(&mut self.explorer_state, len, delta);
+                }
+            }
+            View::Network => step(&mut self.routing_state, self.routes.len(), delta),
+            View::Overview => {}
+        }
+    }
+}
+
+//One filter term: state:listen, port:22, proc:sshd, or text found anywhere in the row
+fn socket_matches(socket: &SocketInfo, process: Option<&str>, term: &str) -> bool {
+    let term = term.to_lowercase();
+    if let Some(state) = term.strip_prefix("state:") {
+        return socket.state.to_lowercase().starts_with(state);
+    }
+    if let Some(port) = term.strip_prefix("port:") {
+        let port = port.parse::<u16>().ok();
+        return port.is_some() && (socket.local.port() == port || socket.remote.port() == port);
+    }
+    if let Some(name) = term.strip_prefix("proc:") {
+        return process.is_some_and(|process| process.to_lowercase().contains(name))
+            || socket.pid.is_some_and(|pid| pid.to_string() == name);
+    }
+    [
+        socket.protocol.label().to_string(),
+        socket.local.to_string(),
+        socket.remote.to_string(),
+        socket.state.to_lowercase(),
+        process.unwrap_or_default().to_lowercase(),
+    ]
+    .iter()
+    .any(|field| field.to_lowercase().contains(&term))
+}
+
+//Move a table cursor, clamped to the table
+fn step(state: &mut TableState, len: usize, delta: isize) {
+    if len == 0 {
+        state.select(None);
+        return;
     }
+    let current = state.selected().unwrap_or(0) as isize;
+    state.select(Some((current + delta).clamp(0, len as isize - 1) as usize));
 }
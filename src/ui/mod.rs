@@ -1,4 +1,8 @@
 mod app;
+#[allow(clippy::module_inception)]
 mod ui;
+mod network;
+mod process;
+mod storage;
 pub mod ratatui;
 mod utils;
@@ -0,0 +1,550 @@
+use ratatui::
+{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    symbols::Marker,
+    widgets::{Axis, Bar, BarChart, BarGroup, Cell, Chart, Clear, Dataset, GraphType, Paragraph, RenderDirection, Row, Sparkline, Table},
+    text::{Line, Span},
+    Frame,
+};
+
+use crate::network::{Endpoint, InterfaceEventKind, Period, Route, QUOTA_ALERT_RATIO, QUOTA_WARN_RATIO};
+use super::app::App;
+use super::utils::{
+    format_bytes, format_count_rate, format_duration, format_network_rate, panel_block, ALERT_COLOR, HEADER_STYLE, LABEL_COLOR, OK_COLOR, SELECTED_STYLE, WARN_COLOR,
+};
+
+//rx/tx history of every interface, one chart each
+pub fn rate_charts(frame: &mut Frame, app: &App, area: Rect)
+{
+    frame.render_widget(Clear, area);
+    let interfaces = app.visible_interfaces();
+    if interfaces.is_empty() {
+        frame.render_widget(Paragraph::new("│ No interface").block(panel_block("Throughput")), area);
+        return;
+    }
+
+    let chart_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, interfaces.len() as u32); interfaces.len()])
+        .split(area);
+
+    for (network, area) in interfaces.iter().zip(chart_chunks.iter()) {
+        //x == seconds before the newest sample, so "now" sits on the right edge
+        let samples: Vec<_> = app.network_monitor.rate_history(&network.interface).collect();
+        let newest = samples.last().map(|(at, _, _)| *at);
+        let seconds_ago = |at: &std::time::Instant| -newest.map_or(0.0, |newest| newest.duration_since(*at).as_secs_f64());
+        let rx: Vec<(f64, f64)> = samples.iter().map(|(at, rx, _)| (seconds_ago(at), *rx)).collect();
+        let tx: Vec<(f64, f64)> = samples.iter().map(|(at, _, tx)| (seconds_ago(at), *tx)).collect();
+
+        let span = rx.first().map_or(0.0, |(x, _)| -x).max(1.0);
+        //Auto-scale on the highest point, with some headroom
+        let peak = rx.iter().chain(tx.iter()).map(|(_, rate)| *rate).fold(0.0, f64::max);
+        let top = (peak * 1.1).max(1_000.0);
+
+        let datasets = vec![
+            Dataset::default()
+            .name(format!("↓ RX {}", format_network_rate(network.rx_rate)))
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(OK_COLOR))
+            .data(&rx),
+            Dataset::default()
+            .name(format!("↑ TX {}", format_network_rate(network.tx_rate)))
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(WARN_COLOR))
+            .data(&tx),
+        ];
+
+        let chart = Chart::new(datasets)
+        .block(panel_block(&format!("{} Throughput", network.interface)))
+        .x_axis(
+            Axis::default()
+            .style(Style::default().fg(LABEL_COLOR))
+            .bounds([-span, 0.0])
+            .labels([
+                Span::raw(format!("-{:.0}s", span)),
+                    Span::raw(format!("-{:.0}s", span / 2.0)),
+                    Span::raw("now"),
+            ]),
+        )
+        .y_axis(
+            Axis::default()
+            .style(Style::default().fg(LABEL_COLOR))
+            .bounds([0.0, top])
+            .labels([
+                Span::raw(format_network_rate(0.0)),
+                    Span::raw(format_network_rate(top / 2.0)),
+                    Span::raw(format_network_rate(top)),
+            ]),
+        )
+        //Keep the RX/TX legend on short charts
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 1)))
+        .style(Style::default().fg(Color::Rgb(169, 177, 214)));
+
+        frame.render_widget(chart, *area);
+    }
+}
+
+//Signal under these dBm is colored as weak / unusable
+const WEAK_SIGNAL: f64 = -67.0;
+const BAD_SIGNAL: f64 = -80.0;
+
+//Wi-Fi link of each wireless interface with its signal history
+pub fn wireless(frame: &mut Frame, app: &App, area: Rect)
+{
+    frame.render_widget(Clear, area);
+    let block = panel_block("Wireless");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, app.wireless.len() as u32); app.wireless.len()])
+        .split(inner);
+
+    for (wireless, area) in app.wireless.iter().zip(rows.iter()) {
+        let color = if wireless.signal < BAD_SIGNAL {
+            ALERT_COLOR
+        } else if wireless.signal < WEAK_SIGNAL {
+            WARN_COLOR
+        } else {
+            OK_COLOR
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                        Constraint::Min(0),
+        ])
+        .split(*area);
+
+        let line = Line::from(vec![
+            Span::styled(format!("│ {} ", wireless.interface), Style::default().fg(Color::Rgb(187, 154, 247))),
+            Span::styled(
+                match (&wireless.ssid, wireless.associated) {
+                    (Some(ssid), _) => ssid.clone(),
+                    (None, true) => "?".to_string(),
+                    (None, false) => "not associated".to_string(),
+                },
+                         Style::default().fg(LABEL_COLOR)
+            ),
+            Span::styled(format!("  {:.0} dBm", wireless.signal), Style::default().fg(color)),
+            Span::styled(
+                format!(
+                    "  quality {:.0}%  noise {}  bitrate {}",
+                    wireless.quality,
+                    wireless.noise.map_or("?".to_string(), |noise| format!("{:.0} dBm", noise)),
+                    wireless.bitrate.map_or("?".to_string(), |bitrate| format!("{:.1} Mb/s", bitrate))
+                ),
+                Style::default().fg(LABEL_COLOR)
+            ),
+        ]);
+        frame.render_widget(Paragraph::new(line), chunks[0]);
+
+        //dBm shifted so -100 dBm sits on the baseline
+        let data: Vec<u64> = app
+            .wireless_monitor
+            .signal_history(&wireless.interface)
+            .rev()
+            .map(|signal| (signal + 100.0).max(0.0) as u64)
+            .collect();
+        frame.render_widget(
+            Sparkline::default()
+            .data(&data)
+            .max(80)
+            .direction(RenderDirection::RightToLeft)
+            .style(Style::default().fg(color)),
+                            chunks[1],
+        );
+    }
+}
+
+//Interfaces plugged, removed or whose counters were reset, newest first
+pub fn interface_events(frame: &mut Frame, app: &App, area: Rect)
+{
+    let rows: Vec<Row> = app.network_monitor
+    .events()
+    .map(|event| {
+        let color = match event.kind {
+            InterfaceEventKind::Appeared => OK_COLOR,
+            InterfaceEventKind::Disappeared => ALERT_COLOR,
+            InterfaceEventKind::CounterReset | InterfaceEventKind::Recreated => WARN_COLOR,
+        };
+        Row::new(vec![
+            Cell::from(format!("{} ago", format_duration(event.at.elapsed().as_secs()))),
+                 Cell::from(event.interface.clone()),
+                 Cell::from(event.kind.label()).style(Style::default().fg(color)),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Length(10),
+                           Constraint::Length(16),
+                           Constraint::Min(10),
+    ])
+    .header(Row::new(vec!["When", "Interface", "Event"]).style(HEADER_STYLE))
+    .block(panel_block("Interface Events"));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(table, area);
+}
+
+fn state_color(state: &str) -> Color
+{
+    match state {
+        "LISTEN" => Color::Rgb(125, 207, 255),
+        "ESTAB" => OK_COLOR,
+        "UNCONN" => LABEL_COLOR,
+        _ => WARN_COLOR,
+    }
+}
+
+//TCP, UDP and Unix sockets like ss -tuxap
+pub fn socket_table(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let sockets = app.visible_sockets();
+    let title = match &app.socket_input {
+        Some(input) => format!("Filter: {}_", input),
+        None if app.socket_filter.is_empty() => {
+            format!("Connections ({}) (/: filter state:listen port:22 proc:sshd)", sockets.len())
+        }
+        None => format!("Connections ({}/{}) matching \"{}\" (Esc: all)", sockets.len(), app.sockets.len(), app.socket_filter),
+    };
+
+    let rows: Vec<Row> = sockets
+    .iter()
+    .map(|(socket, process)| {
+        Row::new(vec![
+            Cell::from(socket.protocol.label()),
+                 Cell::from(socket.state).style(Style::default().fg(state_color(socket.state))),
+                 Cell::from(socket.recv_queue.map_or("-".to_string(), |queue| queue.to_string())),
+                 Cell::from(socket.send_queue.map_or("-".to_string(), |queue| queue.to_string())),
+                 Cell::from(socket.local.to_string()),
+                 Cell::from(socket.remote.to_string()),
+                 Cell::from(match (socket.pid, process) {
+                     (Some(pid), Some(process)) => format!("{} ({})", process, pid),
+                     (Some(pid), None) => pid.to_string(),
+                     _ => "-".to_string(),
+                 }),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Length(5),
+                           Constraint::Length(13),
+                           Constraint::Length(7),
+                           Constraint::Length(7),
+                           Constraint::Percentage(30),
+                           Constraint::Percentage(30),
+                           Constraint::Min(12),
+    ])
+    .header(Row::new(vec!["Proto", "State", "Recv-Q", "Send-Q", "Local", "Remote", "Process"]).style(HEADER_STYLE))
+    .block(panel_block(&title))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut app.socket_state);
+}
+
+//Listening TCP/UDP sockets, wildcard binds stand out
+pub fn listening_table(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let listening = app.listening_sockets();
+    let wildcard = listening.iter().filter(|(socket, _)| socket.is_wildcard()).count();
+    let title = format!("Listening Ports ({}, {} on all interfaces)", listening.len(), wildcard);
+
+    let rows: Vec<Row> = listening
+    .iter()
+    .map(|(socket, process)| {
+        let (address, exposure, color) = match socket.local {
+            Endpoint::Inet(addr) if addr.ip().is_unspecified() => (addr.ip().to_string(), "all interfaces", WARN_COLOR),
+            Endpoint::Inet(addr) if addr.ip().is_loopback() => (addr.ip().to_string(), "loopback", OK_COLOR),
+            Endpoint::Inet(addr) => (addr.ip().to_string(), "address", LABEL_COLOR),
+            Endpoint::Unix(_) => ("-".to_string(), "-", LABEL_COLOR),
+        };
+        Row::new(vec![
+            Cell::from(socket.protocol.label()),
+                 Cell::from(address).style(Style::default().fg(color)),
+                 Cell::from(socket.local.port().map_or("-".to_string(), |port| port.to_string())),
+                 Cell::from(exposure).style(Style::default().fg(color)),
+                 Cell::from(match (socket.pid, process) {
+                     (Some(pid), Some(process)) => format!("{} ({})", process, pid),
+                     (Some(pid), None) => pid.to_string(),
+                     _ => "-".to_string(),
+                 }),
+                 Cell::from(socket.user.clone().unwrap_or_else(|| "-".to_string())),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Length(5),
+                           Constraint::Length(40),
+                           Constraint::Length(6),
+                           Constraint::Length(15),
+                           Constraint::Min(20),
+                           Constraint::Length(16),
+    ])
+    .header(Row::new(vec!["Proto", "Address", "Port", "Exposure", "Process", "User"]).style(HEADER_STYLE))
+    .block(panel_block(&title))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut app.listening_state);
+}
+
+//Retransmits, resets, listen overflows... each with its rate history
+pub fn protocol_health(frame: &mut Frame, app: &App, area: Rect)
+{
+    frame.render_widget(Clear, area);
+    let block = panel_block("Protocol Health");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1); app.protocol_health.len()])
+        .split(inner);
+
+    for (counter, area) in app.protocol_health.iter().zip(rows.iter()) {
+        let color = if counter.rate > 0.0 { ALERT_COLOR } else { OK_COLOR };
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(28),
+                        Constraint::Length(22),
+                        Constraint::Min(0),
+        ])
+        .split(*area);
+        frame.render_widget(
+            Paragraph::new(Span::styled(format!("│ {}", counter.label), Style::default().fg(LABEL_COLOR))),
+                            chunks[0],
+        );
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(format!("{:>8}", format_count_rate(counter.rate)), Style::default().fg(color)),
+                Span::styled(format!(" {:>10}", counter.total), Style::default().fg(LABEL_COLOR)),
+            ])),
+            chunks[1],
+        );
+        //Tenths so slow rates still show, each sparkline scales to its own max
+        let data: Vec<u64> = app
+            .protocol_monitor
+            .history(counter.name)
+            .rev()
+            .map(|rate| (rate * 10.0).round() as u64)
+            .collect();
+        frame.render_widget(
+            Sparkline::default()
+            .data(&data)
+            .direction(RenderDirection::RightToLeft)
+            .style(Style::default().fg(color)),
+                            chunks[2],
+        );
+    }
+}
+
+//Routes, neighbors, gateways and resolvers
+pub fn routing(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    frame.render_widget(Clear, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(6),
+                    Constraint::Length(12),
+    ])
+    .split(area);
+    let bottom_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(65),
+                    Constraint::Percentage(35),
+    ])
+    .split(chunks[1]);
+
+    let rows: Vec<Row> = app.routes
+    .iter()
+    .map(|route| {
+        let destination = if route.is_default() {
+            "default".to_string()
+        } else {
+            format!("{}/{}", route.destination, route.prefix)
+        };
+        Row::new(vec![
+            Cell::from(destination).style(Style::default().fg(if route.is_default() { OK_COLOR } else { LABEL_COLOR })),
+                 Cell::from(route.gateway.map_or("direct".to_string(), |gateway| gateway.to_string())),
+                 Cell::from(route.interface.clone()),
+                 Cell::from(route.metric.to_string()),
+        ])
+    })
+    .collect();
+    let table = Table::new(rows, [
+        Constraint::Percentage(40),
+                           Constraint::Percentage(40),
+                           Constraint::Length(16),
+                           Constraint::Length(8),
+    ])
+    .header(Row::new(vec!["Destination", "Gateway", "Interface", "Metric"]).style(HEADER_STYLE))
+    .block(panel_block(&format!("Routes ({})", app.routes.len())))
+    .row_highlight_style(SELECTED_STYLE);
+    frame.render_stateful_widget(table, chunks[0], &mut app.routing_state);
+
+    let rows: Vec<Row> = app.neighbors
+    .iter()
+    .map(|neighbor| {
+        Row::new(vec![
+            Cell::from(neighbor.address.to_string()),
+                 Cell::from(neighbor.mac.clone()),
+                 Cell::from(neighbor.interface.clone()),
+                 Cell::from(if neighbor.complete { "complete" } else { "incomplete" })
+                     .style(Style::default().fg(if neighbor.complete { OK_COLOR } else { WARN_COLOR })),
+        ])
+    })
+    .collect();
+    let table = Table::new(rows, [
+        Constraint::Length(16),
+                           Constraint::Length(18),
+                           Constraint::Length(10),
+                           Constraint::Min(10),
+    ])
+    .header(Row::new(vec!["Address", "MAC", "Interface", "State"]).style(HEADER_STYLE))
+    .block(panel_block("Neighbors (IPv4)"));
+    frame.render_widget(table, bottom_chunks[0]);
+
+    let value = |text: String| Span::styled(text, Style::default().fg(Color::Rgb(158, 206, 106)));
+    let label = |text: &str| Span::styled(text.to_string(), Style::default().fg(LABEL_COLOR));
+    let mut text = vec![Line::from(Span::styled("├─ Default Gateways ─┤", Style::default().fg(LABEL_COLOR)))];
+    let defaults: Vec<&Route> = app.routes.iter().filter(|route| route.is_default() && route.gateway.is_some()).collect();
+    if defaults.is_empty() {
+        text.push(Line::from(vec![Span::raw("│ "), Span::styled("No default route", Style::default().fg(ALERT_COLOR))]));
+    }
+    for route in defaults {
+        text.push(Line::from(vec![
+            Span::raw("│ "),
+            label(&format!("{}: ", route.interface)),
+            value(route.gateway.map(|gateway| gateway.to_string()).unwrap_or_default()),
+            label(&format!(" metric {}", route.metric)),
+        ]));
+    }
+    text.push(Line::from(Span::styled("├─ DNS ─┤", Style::default().fg(LABEL_COLOR))));
+    if app.dns.nameservers.is_empty() {
+        text.push(Line::from(vec![Span::raw("│ "), Span::styled("No nameserver", Style::default().fg(ALERT_COLOR))]));
+    }
+    for nameserver in &app.dns.nameservers {
+        text.push(Line::from(vec![Span::raw("│ "), label("nameserver "), value(nameserver.clone())]));
+    }
+    if !app.dns.search.is_empty() {
+        text.push(Line::from(vec![Span::raw("│ "), label("search "), value(app.dns.search.join(" "))]));
+    }
+    if !app.dns.options.is_empty() {
+        text.push(Line::from(vec![Span::raw("│ "), label("options "), value(app.dns.options.join(" "))]));
+    }
+    frame.render_widget(Paragraph::new(text).block(panel_block("Gateways & Resolvers")), bottom_chunks[1]);
+}
+
+//Monthly quota use colored like the filesystems
+fn quota_color(ratio: f64) -> Color
+{
+    match ratio {
+        ratio if ratio >= QUOTA_ALERT_RATIO => ALERT_COLOR,
+        ratio if ratio >= QUOTA_WARN_RATIO => WARN_COLOR,
+        _ => OK_COLOR,
+    }
+}
+
+//Persistent totals per interface, hourly, daily and monthly bars of the selected one
+pub fn traffic(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    frame.render_widget(Clear, area);
+    let interfaces: Vec<String> = app.accounted_interfaces().iter().map(|network| network.interface.clone()).collect();
+    let quota = app.config.net_monthly_quota_gb * 1_000_000_000.0;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(interfaces.len() as u16 + 3),
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+    ])
+    .split(area);
+
+    let rows: Vec<Row> = interfaces
+    .iter()
+    .map(|interface| {
+        let today = app.accounting.current(interface, Period::Day);
+        let month = app.accounting.current(interface, Period::Month);
+        let quota_cell = if quota > 0.0 {
+            let ratio = month.total() as f64 / quota;
+            Cell::from(format!("{:.1}%", ratio * 100.0)).style(Style::default().fg(quota_color(ratio)))
+        } else {
+            Cell::from("-")
+        };
+        Row::new(vec![
+            Cell::from(interface.clone()),
+                 Cell::from(format_bytes(today.rx)),
+                 Cell::from(format_bytes(today.tx)),
+                 Cell::from(format_bytes(month.rx)),
+                 Cell::from(format_bytes(month.tx)),
+                 Cell::from(format_bytes(month.total())),
+                 quota_cell,
+        ])
+    })
+    .collect();
+    let title = if quota > 0.0 {
+        format!("Traffic, local time (monthly quota {} per interface)", format_bytes(quota as u64))
+    } else {
+        "Traffic, local time".to_string()
+    };
+    let table = Table::new(rows, [
+        Constraint::Length(16),
+                           Constraint::Length(12),
+                           Constraint::Length(12),
+                           Constraint::Length(12),
+                           Constraint::Length(12),
+                           Constraint::Length(12),
+                           Constraint::Min(8),
+    ])
+    .header(Row::new(vec!["Interface", "Today ↓", "Today ↑", "Month ↓", "Month ↑", "Month", "Quota"]).style(HEADER_STYLE))
+    .block(panel_block(&title))
+    .row_highlight_style(SELECTED_STYLE);
+    frame.render_stateful_widget(table, chunks[0], &mut app.traffic_state);
+
+    let selected = app.traffic_state.selected().unwrap_or(0);
+    let Some(interface) = interfaces.get(selected) else {
+        return;
+    };
+    //Key suffix as label: hour of 2026-10-19T14, day of 2026-10-19, whole 2026-10 for months
+    for (period, count, title, area) in [
+        (Period::Hour, 24, format!("{} last 24 hours", interface), chunks[1]),
+        (Period::Day, 30, format!("{} last 30 days", interface), chunks[2]),
+        (Period::Month, 12, format!("{} last 12 months", interface), chunks[3]),
+    ] {
+        let history = app.accounting.history(interface, period, count);
+        let bars: Vec<Bar> = history
+        .iter()
+        .map(|(key, usage)| {
+            Bar::default()
+            .value(usage.total())
+            .text_value(String::new())
+            .label(Line::from(if period == Period::Month { key.clone() } else { key[key.len() - 2..].to_string() }))
+            .style(Style::default().fg(OK_COLOR))
+        })
+        .collect();
+        let peak = history.iter().map(|(_, usage)| usage.total()).max().unwrap_or(0);
+        let inner_width = area.width.saturating_sub(2);
+        let bar_width = (inner_width / count as u16).saturating_sub(1).max(1);
+        let chart = BarChart::default()
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .block(panel_block(&format!("{} (peak {})", title, format_bytes(peak))))
+        .label_style(Style::default().fg(LABEL_COLOR));
+        frame.render_widget(chart, area);
+    }
+}
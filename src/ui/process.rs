@@ -0,0 +1,348 @@
+use ratatui::
+{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style, Modifier},
+    widgets::{Cell, Clear, Paragraph, RenderDirection, Row, Sparkline, Table},
+    text::{Span, Line},
+    Frame,
+};
+
+use crate::monitoring::lifecycle::LifecycleKind;
+use crate::monitoring::limits::{ALERT_RATIO, WARN_RATIO};
+use crate::monitoring::problems::ProblemKind;
+use super::app::App;
+use super::utils::{
+    format_bytes, format_duration, format_lifetime, format_network_rate, panel_block,
+    ALERT_COLOR, HEADER_STYLE, LABEL_COLOR, OK_COLOR, SELECTED_STYLE, WARN_COLOR,
+};
+
+fn usage_color(cpu: f64) -> Color
+{
+    match cpu {
+        metric if metric > 85.0 => ALERT_COLOR,
+        metric if metric > 50.0 => WARN_COLOR,
+        _ => OK_COLOR,
+    }
+}
+
+pub fn process_table(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let title = match &app.unit_filter {
+        Some(unit) => format!("Processes of {} (Esc: all)", unit),
+        None => "Processes".to_string(),
+    };
+
+    let rows: Vec<Row> = app.visible_processes()
+    .into_iter()
+    .map(|process| {
+        Row::new(vec![
+            Cell::from(process.pid.to_string()),
+                 Cell::from(process.parent.map_or("-".to_string(), |pid| pid.to_string())),
+                 Cell::from(process.name.clone()),
+                 Cell::from(process.status.to_string()),
+                 Cell::from(format!("{:>5.1}%", process.cpu_usage))
+                     .style(Style::default().fg(usage_color(process.cpu_usage as f64))),
+                 Cell::from(format_bytes(process.memory)),
+                 Cell::from(process.command.clone()),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Length(8),
+                           Constraint::Length(8),
+                           Constraint::Length(16),
+                           Constraint::Length(12),
+                           Constraint::Length(8),
+                           Constraint::Length(10),
+                           Constraint::Min(10),
+    ])
+    .header(Row::new(vec!["PID", "PPID", "Name", "State", "CPU", "Memory", "Command"]).style(HEADER_STYLE))
+    .block(panel_block(&title))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut app.process_state);
+}
+
+pub fn unit_table(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let rows: Vec<Row> = app.unit_data
+    .iter()
+    .map(|unit| {
+        let signals = unit.signals.iter().map(|signal| signal.label()).collect::<Vec<_>>().join(", ");
+        Row::new(vec![
+            Cell::from(unit.name.clone()),
+                 Cell::from(format!("{:>5.1}%", unit.cpu_usage))
+                     .style(Style::default().fg(usage_color(unit.cpu_usage))),
+                 Cell::from(format_bytes(unit.memory)),
+                 Cell::from(unit.tasks.to_string()),
+                 Cell::from(signals).style(Style::default().fg(ALERT_COLOR)),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Min(24),
+                           Constraint::Length(8),
+                           Constraint::Length(10),
+                           Constraint::Length(7),
+                           Constraint::Min(20),
+    ])
+    .header(Row::new(vec!["Unit", "CPU", "Memory", "Tasks", "Signals"]).style(HEADER_STYLE))
+    .block(panel_block("systemd Units (Enter: show processes)"))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut app.unit_state);
+}
+
+pub fn event_log(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let rows: Vec<Row> = app.lifecycle_monitor
+    .events()
+    .map(|event| {
+        let (label, color) = match event.kind {
+            LifecycleKind::Started => ("start", OK_COLOR),
+            LifecycleKind::Exited => ("exit", ALERT_COLOR),
+        };
+        Row::new(vec![
+            Cell::from(format!("{} ago", format_duration(event.at.elapsed().as_secs()))),
+                 Cell::from(label).style(Style::default().fg(color)),
+                 Cell::from(event.pid.to_string()),
+                 Cell::from(event.parent.map_or("-".to_string(), |pid| pid.to_string())),
+                 Cell::from(event.lifetime.map_or("-".to_string(), format_lifetime)),
+                 Cell::from(format!("{:>5.1}%", event.peak_cpu))
+                     .style(Style::default().fg(usage_color(event.peak_cpu as f64))),
+                 Cell::from(format_bytes(event.peak_memory)),
+                 Cell::from(event.command.clone()),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Length(10),
+                           Constraint::Length(6),
+                           Constraint::Length(8),
+                           Constraint::Length(8),
+                           Constraint::Length(9),
+                           Constraint::Length(9),
+                           Constraint::Length(10),
+                           Constraint::Min(10),
+    ])
+    .header(Row::new(vec!["When", "Event", "PID", "PPID", "Lifetime", "Peak CPU", "Peak RSS", "Command"]).style(HEADER_STYLE))
+    .block(panel_block(&format!(
+        "Process Lifecycle (/proc polled every {} ms, shorter-lived processes can be missed)",
+                                app.lifecycle_monitor.poll_interval().as_millis()
+    )))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut app.event_state);
+}
+
+pub fn problem_table(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let rows: Vec<Row> = app.problems
+    .iter()
+    .map(|problem| {
+        let color = match problem.kind {
+            ProblemKind::Zombie => WARN_COLOR,
+            ProblemKind::Uninterruptible | ProblemKind::Runaway => ALERT_COLOR,
+        };
+        Row::new(vec![
+            Cell::from(problem.kind.label()).style(Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                 Cell::from(problem.pid.to_string()),
+                 Cell::from(problem.name.clone()),
+                 Cell::from(problem.parent.as_ref().map_or("-".to_string(), |(pid, name)| format!("{} ({})", pid, name))),
+                 Cell::from(format_duration(problem.duration.as_secs())),
+                 Cell::from(format!("{:>5.1}%", problem.cpu_usage))
+                     .style(Style::default().fg(usage_color(problem.cpu_usage as f64))),
+        ])
+    })
+    .collect();
+
+    let title = format!("Problems ({})", app.problems.len());
+    let table = Table::new(rows, [
+        Constraint::Length(9),
+                           Constraint::Length(8),
+                           Constraint::Length(20),
+                           Constraint::Min(20),
+                           Constraint::Length(9),
+                           Constraint::Length(8),
+    ])
+    .header(Row::new(vec!["Problem", "PID", "Name", "Parent", "For", "CPU"]).style(HEADER_STYLE))
+    .block(panel_block(&title))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut app.problem_state);
+}
+
+pub fn limit_table(frame: &mut Frame, app: &App, area: Rect)
+{
+    let rows: Vec<Row> = app.limit_usage
+    .iter()
+    .map(|usage| {
+        let ratio = usage.ratio();
+        let color = match ratio {
+            r if r >= ALERT_RATIO => ALERT_COLOR,
+            r if r >= WARN_RATIO => WARN_COLOR,
+            _ => OK_COLOR,
+        };
+        let bar_width = 20;
+        let filled_width = ((ratio.min(1.0)) * bar_width as f64).round() as usize;
+        Row::new(vec![
+            Cell::from(usage.resource),
+                 Cell::from(usage.owner.clone()),
+                 Cell::from(format!("{} / {}", usage.used, usage.limit)),
+                 Cell::from(format!("{}{}", "█".repeat(filled_width), "░".repeat(bar_width - filled_width)))
+                     .style(Style::default().fg(color)),
+                 Cell::from(format!("{:>5.1}%", ratio * 100.0))
+                     .style(Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Length(10),
+                           Constraint::Min(20),
+                           Constraint::Length(22),
+                           Constraint::Length(20),
+                           Constraint::Length(7),
+    ])
+    .header(Row::new(vec!["Resource", "Owner", "Used / Limit", "", "Usage"]).style(HEADER_STYLE))
+    .block(panel_block("Resource Limits"));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(table, area);
+}
+
+pub fn pinned_view(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(40),
+                    Constraint::Percentage(60),
+    ])
+    .split(area);
+
+    let rows: Vec<Row> = app.pin_monitor.pins
+    .iter()
+    .map(|pin| {
+        let (status, color) = match pin.pid {
+            Some(pid) => (pid.to_string(), OK_COLOR),
+            None => ("down".to_string(), ALERT_COLOR),
+        };
+        Row::new(vec![
+            Cell::from(pin.pattern.describe()),
+                 Cell::from(status).style(Style::default().fg(color)),
+                 Cell::from(pin.restarts.len().to_string())
+                     .style(Style::default().fg(if pin.restarts.is_empty() { LABEL_COLOR } else { WARN_COLOR })),
+        ])
+    })
+    .collect();
+
+    let title = match &app.pin_input {
+        Some(input) => format!("Pin command: {}_", input),
+        None => "Pinned (a: add, d: remove, p in Processes)".to_string(),
+    };
+    let table = Table::new(rows, [
+        Constraint::Min(16),
+                           Constraint::Length(8),
+                           Constraint::Length(8),
+    ])
+    .header(Row::new(vec!["Pattern", "PID", "Restarts"]).style(HEADER_STYLE))
+    .block(panel_block(&title))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, chunks[0], &mut app.pin_state);
+
+    let selected = app.pin_state.selected().and_then(|i| app.pin_monitor.pins.get(i));
+    let Some(pin) = selected else {
+        frame.render_widget(Paragraph::new("│ Select a pin").block(panel_block("History")), chunks[1]);
+        return;
+    };
+
+    let detail = panel_block(&format!("History of {}", pin.pattern.describe()));
+    let inner = detail.inner(chunks[1]);
+    frame.render_widget(detail, chunks[1]);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+                    Constraint::Ratio(1, 5),
+                    Constraint::Ratio(1, 5),
+                    Constraint::Ratio(1, 5),
+                    Constraint::Ratio(1, 5),
+                    Constraint::Ratio(1, 5),
+    ])
+    .split(inner);
+
+    let attached = pin.attached_at.map_or("never".to_string(), |at| format!("{} ago", format_duration(at.elapsed().as_secs())));
+    let restarts = pin.restarts.iter().rev().take(5)
+    .map(|at| format!("{} ago", format_duration(at.elapsed().as_secs())))
+    .collect::<Vec<_>>()
+    .join(", ");
+    frame.render_widget(Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled("│ Attached: ", Style::default().fg(LABEL_COLOR)),
+                   Span::raw(attached),
+                   Span::styled("  Restarts: ", Style::default().fg(LABEL_COLOR)),
+                   Span::styled(pin.restarts.len().to_string(), Style::default().fg(WARN_COLOR)),
+        ]),
+        Line::from(vec![
+            Span::styled("│ Last restarts: ", Style::default().fg(LABEL_COLOR)),
+                   Span::raw(if restarts.is_empty() { "-".to_string() } else { restarts }),
+        ]),
+    ]), rows[0]);
+
+    let last = pin.history.back();
+    let charts: [(String, Vec<u64>, Color); 5] = [
+        (
+            format!("CPU {:.1}%", last.map_or(0.0, |sample| sample.cpu_usage)),
+            pin.history.iter().rev().map(|sample| sample.cpu_usage.round() as u64).collect(),
+            OK_COLOR,
+        ),
+        (
+            format!("Memory {}", format_bytes(last.map_or(0, |sample| sample.memory))),
+            pin.history.iter().rev().map(|sample| sample.memory).collect(),
+            Color::Rgb(187, 154, 247),
+        ),
+        (
+            format!("Read {}", format_network_rate(last.map_or(0.0, |sample| sample.read_rate))),
+            pin.history.iter().rev().map(|sample| sample.read_rate as u64).collect(),
+            Color::Rgb(125, 207, 255),
+        ),
+        (
+            format!("Write {}", format_network_rate(last.map_or(0.0, |sample| sample.write_rate))),
+            pin.history.iter().rev().map(|sample| sample.write_rate as u64).collect(),
+            WARN_COLOR,
+        ),
+        (
+            format!("FDs {}", last.and_then(|sample| sample.fds).map_or("?".to_string(), |fds| fds.to_string())),
+            pin.history.iter().rev().map(|sample| sample.fds.unwrap_or(0)).collect(),
+            ALERT_COLOR,
+        ),
+    ];
+    for ((label, data, color), area) in charts.into_iter().zip(rows.iter().skip(1)) {
+        let chart_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                        Constraint::Min(0),
+        ])
+        .split(*area);
+        frame.render_widget(Paragraph::new(Span::styled(format!("│ {}", label), Style::default().fg(LABEL_COLOR))), chart_chunks[0]);
+        frame.render_widget(
+            Sparkline::default()
+            .data(&data)
+            .direction(RenderDirection::RightToLeft)
+            .style(Style::default().fg(color)),
+                            chart_chunks[1],
+        );
+    }
+}
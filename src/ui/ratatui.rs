@@ -28,8 +28,11 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()>
         terminal.draw(|frame| ui::draw(frame, &mut app))?;
         if event::poll(std::time::Duration::from_millis(250))? {
             if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press && key.code == KeyCode::Char('q') {
-                    break;
+                if key.kind == KeyEventKind::Press {
+                    if key.code == KeyCode::Char('q') && !app.is_editing() {
+                        break;
+                    }
+                    app.on_key(key.code);
                 }
             }
         }
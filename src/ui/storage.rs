@@ -0,0 +1,349 @@
+use ratatui::
+{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style, Modifier},
+    widgets::{Cell, Clear, Paragraph, RenderDirection, Row, Sparkline, Table},
+    text::{Span, Line},
+    Frame,
+};
+
+use crate::config::Config;
+use crate::storage::BlockKind;
+use super::app::App;
+use super::utils::{
+    format_bytes, format_duration, format_network_rate, panel_block,
+    ALERT_COLOR, HEADER_STYLE, LABEL_COLOR, OK_COLOR, SELECTED_STYLE, WARN_COLOR,
+};
+
+//Same thresholds for space and inodes
+fn fill_color(config: &Config, percent: f64) -> Color
+{
+    match percent {
+        p if p >= config.fs_alert_percent => ALERT_COLOR,
+        p if p >= config.fs_warn_percent => WARN_COLOR,
+        _ => OK_COLOR,
+    }
+}
+
+fn fill_bar(percent: f64) -> String
+{
+    let bar_width = 10;
+    let filled_width = ((percent.clamp(0.0, 100.0) / 100.0) * bar_width as f64).round() as usize;
+    format!("{}{}", "█".repeat(filled_width), "░".repeat(bar_width - filled_width))
+}
+
+pub fn filesystem_table(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let config = &app.config;
+    let horizon = config.fs_full_horizon_hours * 3600.0;
+    let rows: Vec<Row> = app.filesystems
+    .iter()
+    .map(|filesystem| {
+        let use_percent = filesystem.use_percent();
+        let use_color = fill_color(config, use_percent);
+        let inode_cell = match filesystem.inode_percent() {
+            Some(percent) => Cell::from(format!("{:>5.1}%", percent))
+                .style(Style::default().fg(fill_color(config, percent))),
+            None => Cell::from("    -"),
+        };
+        let full_cell = match app.usage_trend.time_to_full(filesystem) {
+            Some(left) if left.as_secs_f64() <= horizon => Cell::from(format!("~{}", format_duration(left.as_secs())))
+                .style(Style::default().fg(ALERT_COLOR).add_modifier(Modifier::BOLD)),
+            Some(left) => Cell::from(format!("~{}", format_duration(left.as_secs()))),
+            None => Cell::from("-"),
+        };
+        //Hung mount, the numbers are the last ones it gave
+        let use_cell = if filesystem.stale {
+            Cell::from("stale").style(Style::default().fg(ALERT_COLOR).add_modifier(Modifier::BOLD))
+        } else {
+            Cell::from(format!("{:>5.1}%", use_percent))
+                .style(Style::default().fg(use_color).add_modifier(Modifier::BOLD))
+        };
+        Row::new(vec![
+            Cell::from(filesystem.mount_point.clone()),
+                 Cell::from(filesystem.device.clone()),
+                 Cell::from(filesystem.fs_type.clone()),
+                 Cell::from(format_bytes(filesystem.total)),
+                 Cell::from(format_bytes(filesystem.used)),
+                 Cell::from(format_bytes(filesystem.available)),
+                 Cell::from(fill_bar(use_percent)).style(Style::default().fg(use_color)),
+                 use_cell,
+                 inode_cell,
+                 full_cell,
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Min(16),
+                           Constraint::Min(16),
+                           Constraint::Length(8),
+                           Constraint::Length(10),
+                           Constraint::Length(10),
+                           Constraint::Length(10),
+                           Constraint::Length(10),
+                           Constraint::Length(7),
+                           Constraint::Length(7),
+                           Constraint::Length(9),
+    ])
+    .header(Row::new(vec!["Mount", "Device", "Type", "Size", "Used", "Avail", "", "Use%", "Inodes", "Full in"]).style(HEADER_STYLE))
+    .block(panel_block("Filesystems (Enter: explore)"))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut app.filesystem_state);
+}
+
+pub fn disk_io(frame: &mut Frame, app: &App, area: Rect)
+{
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(60),
+                    Constraint::Percentage(40),
+    ])
+    .split(area);
+
+    let rows: Vec<Row> = app.disk_io
+    .iter()
+    .map(|disk| {
+        let util_color = match disk.utilization {
+            u if u >= 90.0 => ALERT_COLOR,
+            u if u >= 60.0 => WARN_COLOR,
+            _ => OK_COLOR,
+        };
+        Row::new(vec![
+            Cell::from(disk.label.clone()),
+                 Cell::from(format_network_rate(disk.read_rate)).style(Style::default().fg(OK_COLOR)),
+                 Cell::from(format_network_rate(disk.write_rate)).style(Style::default().fg(WARN_COLOR)),
+                 Cell::from(format!("{:.0}/{:.0}", disk.read_iops, disk.write_iops)),
+                 Cell::from(format!("{:.1} ms", disk.await_ms)),
+                 Cell::from(format!("{:.2}", disk.queue_depth)),
+                 Cell::from(format!("{:>5.1}%", disk.utilization))
+                     .style(Style::default().fg(util_color).add_modifier(Modifier::BOLD)),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Min(10),
+                           Constraint::Length(11),
+                           Constraint::Length(11),
+                           Constraint::Length(11),
+                           Constraint::Length(9),
+                           Constraint::Length(6),
+                           Constraint::Length(7),
+    ])
+    .header(Row::new(vec!["Device", "Read", "Write", "IOPS r/w", "Await", "Queue", "Util"]).style(HEADER_STYLE))
+    .block(panel_block("Disk I/O"));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(table, chunks[0]);
+
+    //Read and write sparklines stacked, one pair per device
+    let history = panel_block("I/O History");
+    let inner = history.inner(chunks[1]);
+    frame.render_widget(history, chunks[1]);
+
+    let per_device = 3;
+    let visible = (inner.height / per_device) as usize;
+    let device_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(per_device); visible])
+        .split(inner);
+
+    for (disk, area) in app.disk_io.iter().zip(device_chunks.iter()) {
+        let lines = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1); 3])
+            .split(*area);
+        frame.render_widget(Paragraph::new(Line::from(vec![
+            Span::styled(format!("│ {} ", disk.label), Style::default().fg(LABEL_COLOR).add_modifier(Modifier::BOLD)),
+                         Span::styled(format!("↓ {} ", format_network_rate(disk.read_rate)), Style::default().fg(OK_COLOR)),
+                         Span::styled(format!("↑ {}", format_network_rate(disk.write_rate)), Style::default().fg(WARN_COLOR)),
+                         Span::styled(
+                             format!("  peak ↓ {} ↑ {}", format_network_rate(disk.read_peak), format_network_rate(disk.write_peak)),
+                                      Style::default().fg(LABEL_COLOR)
+                         ),
+        ])), lines[0]);
+        let reads: Vec<u64> = app.disk_io_monitor.history(&disk.device).rev().map(|(read, _)| *read as u64).collect();
+        let writes: Vec<u64> = app.disk_io_monitor.history(&disk.device).rev().map(|(_, write)| *write as u64).collect();
+        frame.render_widget(
+            Sparkline::default()
+            .data(&reads)
+            .direction(RenderDirection::RightToLeft)
+            .style(Style::default().fg(OK_COLOR)),
+                            lines[1],
+        );
+        frame.render_widget(
+            Sparkline::default()
+            .data(&writes)
+            .direction(RenderDirection::RightToLeft)
+            .style(Style::default().fg(WARN_COLOR)),
+                            lines[2],
+        );
+    }
+}
+
+pub fn storage_tree(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    let rows: Vec<Row> = app.storage_stack
+    .iter()
+    .map(|node| {
+        let name_color = match node.kind {
+            BlockKind::Disk => OK_COLOR,
+            BlockKind::Partition => LABEL_COLOR,
+            _ => Color::Rgb(187, 154, 247),
+        };
+        let (mut state, mut state_color) = match &node.raid {
+            Some(raid) if raid.degraded => (format!("{} DEGRADED", raid.members), ALERT_COLOR),
+            Some(raid) => (raid.members.clone(), OK_COLOR),
+            None => (String::new(), LABEL_COLOR),
+        };
+        if let Some(sync) = node.raid.as_ref().and_then(|raid| raid.sync.as_ref()) {
+            state = format!("{} {}", state, sync);
+            state_color = WARN_COLOR;
+        }
+        //Live I/O of this layer, to follow it down the stack
+        let io = app.disk_io
+        .iter()
+        .find(|disk| disk.device == node.device)
+        .map_or(String::new(), |disk| format!(
+            "↓{} ↑{} {:.0}%",
+            format_network_rate(disk.read_rate),
+                                               format_network_rate(disk.write_rate),
+                                               disk.utilization
+        ));
+        Row::new(vec![
+            Cell::from(Line::from(vec![
+                Span::styled(node.prefix.clone(), Style::default().fg(LABEL_COLOR)),
+                       Span::styled(node.label.clone(), Style::default().fg(name_color).add_modifier(Modifier::BOLD)),
+            ])),
+            Cell::from(node.kind.label()),
+                 Cell::from(format_bytes(node.size)),
+                 Cell::from(node.mount.clone().unwrap_or_default()),
+                 Cell::from(state).style(Style::default().fg(state_color)),
+                 Cell::from(io),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Min(24),
+                           Constraint::Length(7),
+                           Constraint::Length(10),
+                           Constraint::Min(16),
+                           Constraint::Min(18),
+                           Constraint::Length(30),
+    ])
+    .header(Row::new(vec!["Device", "Type", "Size", "Mount", "State", "I/O"]).style(HEADER_STYLE))
+    .block(panel_block("Storage Stack"))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut app.storage_state);
+}
+
+pub fn explorer(frame: &mut Frame, app: &mut App, area: Rect)
+{
+    frame.render_widget(Clear, area);
+    let Some(scan) = &app.explorer else {
+        frame.render_widget(
+            Paragraph::new("│ Select a filesystem in Disks and press Enter").block(panel_block("Explorer")),
+                            area,
+        );
+        return;
+    };
+
+    let progress = &app.explorer_progress;
+    let total: u64 = progress.entries.iter().map(|entry| entry.size).sum();
+    let largest = progress.entries.first().map_or(0, |entry| entry.size);
+    let status = match (progress.done, progress.cancelled) {
+        (true, true) => "cancelled",
+        (true, false) => "done",
+        _ => "scanning, c: cancel",
+    };
+    let title = format!(
+        "{} {} ({} files, {} errors, {}) Enter: open, Backspace: up",
+        scan.root.display(),
+                        format_bytes(total),
+                        progress.files,
+                        progress.errors,
+                        status
+    );
+
+    let rows: Vec<Row> = progress.entries
+    .iter()
+    .map(|entry| {
+        let share = if largest > 0 { entry.size as f64 / largest as f64 * 100.0 } else { 0.0 };
+        let (name, color) = if entry.is_dir {
+            (format!("{}/", entry.name), Color::Rgb(125, 207, 255))
+        } else {
+            (entry.name.clone(), LABEL_COLOR)
+        };
+        Row::new(vec![
+            Cell::from(format_bytes(entry.size)),
+                 Cell::from(fill_bar(share)).style(Style::default().fg(OK_COLOR)),
+                 Cell::from(format!("{:>5.1}%", if total > 0 { entry.size as f64 / total as f64 * 100.0 } else { 0.0 })),
+                 Cell::from(name).style(Style::default().fg(color)),
+        ])
+    })
+    .collect();
+
+    let table = Table::new(rows, [
+        Constraint::Length(10),
+                           Constraint::Length(10),
+                           Constraint::Length(7),
+                           Constraint::Min(20),
+    ])
+    .header(Row::new(vec!["Size", "", "Share", "Name"]).style(HEADER_STYLE))
+    .block(panel_block(&title))
+    .row_highlight_style(SELECTED_STYLE);
+
+    frame.render_stateful_widget(table, area, &mut app.explorer_state);
+}
+
+pub fn nfs_stats(frame: &mut Frame, app: &App, area: Rect)
+{
+    let mut rows: Vec<Row> = Vec::new();
+    for mount in &app.nfs_mounts {
+        let retrans_color = if mount.retrans_rate > 0.0 { ALERT_COLOR } else { LABEL_COLOR };
+        rows.push(Row::new(vec![
+            Cell::from(format!("{} on {} ({})", mount.export, mount.mount_point, mount.fs_type))
+                .style(Style::default().fg(Color::Rgb(187, 154, 247)).add_modifier(Modifier::BOLD)),
+                           Cell::from(format!("{:.1}", mount.ops_rate)).style(Style::default().add_modifier(Modifier::BOLD)),
+                           Cell::from(""),
+                           Cell::from(""),
+                           Cell::from(format!("{:.1}", mount.retrans_rate)).style(Style::default().fg(retrans_color)),
+        ]));
+        for op in &mount.ops {
+            let rtt_color = match op.rtt_ms {
+                rtt if rtt >= 100.0 => ALERT_COLOR,
+                rtt if rtt >= 20.0 => WARN_COLOR,
+                _ => OK_COLOR,
+            };
+            rows.push(Row::new(vec![
+                Cell::from(format!("│ {}", op.op)),
+                               Cell::from(format!("{:.1}", op.ops_rate)),
+                               Cell::from(format!("{:.1} ms", op.rtt_ms)).style(Style::default().fg(rtt_color)),
+                               Cell::from(format!("{:.1} ms", op.exec_ms)),
+                               Cell::from(format!("{:.1}", op.retrans_rate))
+                                   .style(Style::default().fg(if op.retrans_rate > 0.0 { ALERT_COLOR } else { LABEL_COLOR })),
+            ]));
+        }
+    }
+
+    let title = if app.nfs_mounts.is_empty() { "NFS Client (no NFS mounts)" } else { "NFS Client" };
+    let table = Table::new(rows, [
+        Constraint::Min(30),
+                           Constraint::Length(9),
+                           Constraint::Length(10),
+                           Constraint::Length(10),
+                           Constraint::Length(10),
+    ])
+    .header(Row::new(vec!["Mount / Op", "Ops/s", "RTT", "Exec", "Retrans/s"]).style(HEADER_STYLE))
+    .block(panel_block(title));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(table, area);
+}
@@ -2,24 +2,22 @@ use ratatui::
 {
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style, Modifier},
-    widgets::{Block, Borders, Paragraph, Wrap, BorderType, Clear},
+    widgets::{Block, Borders, Paragraph, Wrap, BorderType, Clear, Tabs},
     text::{Span, Line},
     prelude::Alignment,
     Frame,
 };
 
 use crate::syst::infos::get_system_info;
-use super::app::App;
-use super::utils::{format_network_rate, format_network_total, info_line};
-
-//STYLE
-const TITLE_STYLE: Style = Style::new()
-    .fg(Color::Rgb(158,206,106))
-    .add_modifier(Modifier::BOLD);
-
-const BORDER_STYLE: Style = Style::new()
-    .fg(Color::Rgb(86,95,137))
-    .add_modifier(Modifier::BOLD);
+use crate::network::AddressScope;
+use super::app::{App, View};
+use super::network;
+use super::process;
+use super::storage;
+use super::utils::{
+    format_count_rate, format_network_rate, format_network_total, info_line, ALERT_COLOR, BORDER_STYLE, LABEL_COLOR, OK_COLOR,
+    TITLE_STYLE, WARN_COLOR,
+};
 
 const MIN_SYS_INFO_HEIGHT: u16 = 7;
 const MIN_CPU_INFO_HEIGHT: u16 = 15;
@@ -30,15 +28,122 @@ pub fn draw(frame: &mut Frame, app: &mut App)
     //Window size
     let term_size = frame.area();
 
+    //Tabs line on top, selected view below
+    let root_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .horizontal_margin(1)
+        .constraints([
+            Constraint::Length(1),
+                    Constraint::Min(0),
+    ])
+    .split(term_size);
+
+    view_tabs(frame, app, root_chunks[0]);
+    match app.view {
+        View::Overview => overview(frame, app, root_chunks[1]),
+        View::Network => {
+            let network_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(40),
+                            Constraint::Percentage(60),
+            ])
+            .split(root_chunks[1]);
+            let left_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(0),
+                            Constraint::Length(8),
+            ])
+            .split(network_chunks[0]);
+            network_info(frame, app, left_chunks[0]);
+            network::interface_events(frame, app, left_chunks[1]);
+            //Wireless panel only on machines with Wi-Fi
+            let mut constraints = vec![Constraint::Min(0), Constraint::Length(app.protocol_health.len() as u16 + 2)];
+            if !app.wireless.is_empty() {
+                constraints.insert(0, Constraint::Percentage(30));
+            }
+            let right_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(constraints)
+                .split(network_chunks[1]);
+            if !app.wireless.is_empty() {
+                network::wireless(frame, app, right_chunks[0]);
+            }
+            network::routing(frame, app, right_chunks[right_chunks.len() - 2]);
+            network::protocol_health(frame, app, right_chunks[right_chunks.len() - 1]);
+        }
+        View::Connections => network::socket_table(frame, app, root_chunks[1]),
+        View::Listening => network::listening_table(frame, app, root_chunks[1]),
+        View::Traffic => network::traffic(frame, app, root_chunks[1]),
+        View::Processes => process::process_table(frame, app, root_chunks[1]),
+        View::Units => process::unit_table(frame, app, root_chunks[1]),
+        View::Events => process::event_log(frame, app, root_chunks[1]),
+        View::Problems => {
+            let problem_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Percentage(50),
+                            Constraint::Percentage(50),
+            ])
+            .split(root_chunks[1]);
+            process::problem_table(frame, app, problem_chunks[0]);
+            process::limit_table(frame, app, problem_chunks[1]);
+        }
+        View::Pinned => process::pinned_view(frame, app, root_chunks[1]),
+        View::Disks => {
+            let disk_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Percentage(50),
+                            Constraint::Percentage(50),
+            ])
+            .split(root_chunks[1]);
+            storage::filesystem_table(frame, app, disk_chunks[0]);
+            storage::disk_io(frame, app, disk_chunks[1]);
+        }
+        View::Storage => {
+            let storage_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Percentage(50),
+                            Constraint::Percentage(50),
+            ])
+            .split(root_chunks[1]);
+            storage::storage_tree(frame, app, storage_chunks[0]);
+            storage::nfs_stats(frame, app, storage_chunks[1]);
+        }
+        View::Explorer => storage::explorer(frame, app, root_chunks[1]),
+    }
+}
+
+fn view_tabs(frame: &mut Frame, app: &App, area: Rect)
+{
+    let warnings = app.warning_count();
+    let quota_warnings = app.quota_warnings();
+    let tabs = Tabs::new(View::ALL.iter().map(|view| match view {
+        View::Problems if warnings > 0 => format!("{} !{}", view.title(), warnings),
+        View::Traffic if quota_warnings > 0 => format!("{} !{}", view.title(), quota_warnings),
+        _ => view.title().to_string(),
+    }))
+    .select(app.view.index())
+    .style(BORDER_STYLE)
+    .highlight_style(TITLE_STYLE.add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(tabs, area);
+}
+
+fn overview(frame: &mut Frame, app: &mut App, area: Rect)
+{
     //Split horizontal term by 2 50/50
     let horizontal_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .margin(1)
+        .vertical_margin(1)
         .constraints([
             Constraint::Percentage(50),
                     Constraint::Percentage(50),
     ])
-    .split(term_size);
+    .split(area);
 
     //Create column split; showing 2 blocks
     let left_chunks = Layout::default()
@@ -49,10 +154,20 @@ pub fn draw(frame: &mut Frame, app: &mut App)
     ])
     .split(horizontal_chunks[0]);
 
+    //Network counters on top of their rate history
+    let right_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+                    Constraint::Percentage(40),
+    ])
+    .split(horizontal_chunks[1]);
+
     //Impl
     system_info(frame, left_chunks[0]);
     cpu_info(frame, app, left_chunks[1]);
-    network_info(frame, app, horizontal_chunks[1]);
+    network_info(frame, app, right_chunks[0]);
+    network::rate_charts(frame, app, right_chunks[1]);
 }
 
 //Calling syst info, UI creation
@@ -157,7 +272,10 @@ fn network_info(frame: &mut Frame, app: &mut App, area: Rect)
     let network_info = Block::default()
     .title(Line::from(vec![
         Span::raw("╭─"),
-                      Span::styled(" Network Activity ", TITLE_STYLE),
+                      Span::styled(
+                          format!(" Network Activity (l: {} link-local) ", if app.show_link_local { "hide" } else { "show" }),
+                              TITLE_STYLE
+                      ),
                       Span::raw("─╮"),
     ]))
     .title_alignment(Alignment::Center)
@@ -167,15 +285,18 @@ fn network_info(frame: &mut Frame, app: &mut App, area: Rect)
 
     let mut text = Vec::new();
 
-    for network in &app.network_data
+    for network in app.visible_interfaces()
     {
         text.push(Line::from(vec![
             Span::raw("├"),
                              Span::raw("─".repeat(3)),
                              Span::styled(
                                  format!(" {} ", network.interface),
-                                     Style::default().fg(Color::Rgb(187, 154, 247)).add_modifier(Modifier::BOLD)
+                                     Style::default()
+                                     .fg(if network.packets.has_faults() { ALERT_COLOR } else { Color::Rgb(187, 154, 247) })
+                                     .add_modifier(Modifier::BOLD)
                              ),
+                             Span::styled(format!("{} ", network.kind.label()), Style::default().fg(LABEL_COLOR)),
                              Span::raw("─".repeat(20)),
                              Span::raw("┤"),
         ]));
@@ -187,15 +308,79 @@ fn network_info(frame: &mut Frame, app: &mut App, area: Rect)
                                           Style::default().fg(Color::Rgb(158, 206, 106))
                              ),
         ]));
+        let (shown, hidden): (Vec<_>, Vec<_>) = network
+        .addresses
+        .iter()
+        .partition(|address| app.show_link_local || address.scope != AddressScope::LinkLocal);
+        for address in &shown
+        {
+            text.push(Line::from(vec![
+                Span::raw("│ "),
+                                 Span::styled(format!("{}: ", address.family()), Style::default().fg(Color::Rgb(169, 177, 214))),
+                                 Span::styled(
+                                     format!("{}/{}", address.address, address.prefix),
+                                              Style::default().fg(Color::Rgb(158, 206, 106))
+                                 ),
+                                 Span::styled(format!(" {}", address.scope.label()), Style::default().fg(LABEL_COLOR)),
+            ]));
+        }
+        if shown.is_empty()
+        {
+            text.push(Line::from(vec![
+                Span::raw("│ "),
+                                 Span::styled("IP: ".to_string(), Style::default().fg(Color::Rgb(169, 177, 214))),
+                                 Span::styled("No address", Style::default().fg(Color::Rgb(158, 206, 106))),
+            ]));
+        }
+        if !hidden.is_empty()
+        {
+            text.push(Line::from(vec![
+                Span::raw("│ "),
+                                 Span::styled(format!("+{} link-local", hidden.len()), Style::default().fg(LABEL_COLOR)),
+            ]));
+        }
+        let link = &network.link;
+        //Renegotiated below what the link did earlier: bad cable, port or autonegotiation
+        let slow = matches!((link.speed, network.best_speed), (Some(speed), Some(best)) if speed < best);
         text.push(Line::from(vec![
             Span::raw("│ "),
-                             Span::styled("IP: ".to_string(), Style::default().fg(Color::Rgb(169, 177, 214))),
+                             Span::styled("Link: ", Style::default().fg(Color::Rgb(169, 177, 214))),
                              Span::styled(
-                                 network.ip_network.to_string(),
-                                          Style::default().fg(Color::Rgb(158, 206, 106))
+                                 link.operstate.clone(),
+                                     Style::default().fg(if link.is_up() { OK_COLOR } else { ALERT_COLOR })
+                             ),
+                             Span::styled(
+                                 match (link.speed, network.best_speed)
+                                 {
+                                     (Some(speed), Some(best)) if slow => format!(" {}Mb/s (was {})", speed, best),
+                                     (Some(speed), _) => format!(" {}Mb/s", speed),
+                                     _ => String::new(),
+                                 },
+                                     Style::default().fg(if slow { WARN_COLOR } else { OK_COLOR })
+                             ),
+                             Span::styled(
+                                 format!(
+                                     "{}  MTU {}  carrier changes {}",
+                                     link.duplex.as_ref().map_or(String::new(), |duplex| format!(" {}", duplex)),
+                                     link.mtu,
+                                     link.carrier_changes
+                                 ),
+                                 Style::default().fg(LABEL_COLOR)
                              ),
         ]));
         text.push(Line::from(vec![Span::raw("├─ Transfer Rates ─┤")]));
+        //Share of the link speed, when the driver reports one
+        let utilization = |rate: f64| {
+            let percent = link.utilization(rate);
+            Span::styled(
+                percent.map_or(String::new(), |percent| format!(" ({:.1}%)", percent)),
+                         Style::default().fg(match percent {
+                             Some(percent) if percent >= 90.0 => ALERT_COLOR,
+                             Some(percent) if percent >= 70.0 => WARN_COLOR,
+                             _ => LABEL_COLOR,
+                         })
+            )
+        };
         text.push(Line::from(vec![
             Span::raw("│ "),
                              Span::styled("↓ RX: ", Style::default().fg(Color::Rgb(158, 206, 106))),
@@ -203,13 +388,49 @@ fn network_info(frame: &mut Frame, app: &mut App, area: Rect)
                                  format_network_rate(network.rx_rate),
                                      Style::default().fg(Color::Rgb(187, 154, 247))
                              ),
+                             utilization(network.rx_rate),
                              Span::raw("  "),
                              Span::styled("↑ TX: ", Style::default().fg(Color::Rgb(224, 175, 104))),
                              Span::styled(
                                  format_network_rate(network.tx_rate),
                                      Style::default().fg(Color::Rgb(187, 154, 247))
                              ),
+                             utilization(network.tx_rate),
         ]));
+        text.push(Line::from(vec![
+            Span::raw("│ "),
+                             Span::styled("Peak ", Style::default().fg(LABEL_COLOR)),
+                             Span::styled(
+                                 format!("↓ {}  ↑ {}", format_network_rate(network.rx_peak), format_network_rate(network.tx_peak)),
+                                     Style::default().fg(LABEL_COLOR)
+                             ),
+        ]));
+        text.push(Line::from(vec![Span::raw("├─ Packets ─┤")]));
+        let packets = &network.packets;
+        text.push(Line::from(vec![
+            Span::raw("│ "),
+                             Span::styled("↓ ", Style::default().fg(Color::Rgb(158, 206, 106))),
+                             Span::styled(format_count_rate(packets.rx_packets), Style::default().fg(Color::Rgb(187, 154, 247))),
+                             Span::raw("  "),
+                             Span::styled("↑ ", Style::default().fg(Color::Rgb(224, 175, 104))),
+                             Span::styled(format_count_rate(packets.tx_packets), Style::default().fg(Color::Rgb(187, 154, 247))),
+                             Span::styled("  mcast ", Style::default().fg(LABEL_COLOR)),
+                             Span::styled(format_count_rate(packets.multicast), Style::default().fg(Color::Rgb(187, 154, 247))),
+        ]));
+        //Red as soon as anything is lost or broken
+        let fault = |label: &str, rate: f64| {
+            vec![
+                Span::styled(format!("{} ", label), Style::default().fg(LABEL_COLOR)),
+                Span::styled(format_count_rate(rate), Style::default().fg(if rate > 0.0 { ALERT_COLOR } else { OK_COLOR })),
+                Span::raw(" "),
+            ]
+        };
+        let mut faults = vec![Span::raw("│ ")];
+        faults.extend(fault("err", packets.rx_errors + packets.tx_errors));
+        faults.extend(fault("drop", packets.rx_dropped + packets.tx_dropped));
+        faults.extend(fault("fifo", packets.fifo));
+        faults.extend(fault("coll", packets.collisions));
+        text.push(Line::from(faults));
         text.push(Line::from(vec![Span::raw("├─ Total Transfer ─┤")]));
 
         text.push(Line::from(vec![
@@ -235,6 +456,20 @@ fn network_info(frame: &mut Frame, app: &mut App, area: Rect)
         ]));
     }
 
+    let hidden = app.network_data.iter().filter(|network| network.hidden).count();
+    if hidden > 0
+    {
+        let summary = if app.show_all_interfaces {
+            format!("{} filtered interfaces shown (a: hide)", hidden)
+        } else {
+            format!("{} interfaces hidden (a: show all)", hidden)
+        };
+        text.push(Line::from(vec![
+            Span::raw("│ "),
+                             Span::styled(summary, Style::default().fg(LABEL_COLOR)),
+        ]));
+    }
+
     let network_paragraph = Paragraph::new(text)
     .block(network_info)
     .alignment(Alignment::Left)
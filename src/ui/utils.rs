@@ -1,9 +1,35 @@
+use std::time::Duration;
+
 use ratatui::
 {
     style::{Color, Style, Modifier},
+    widgets::{Block, Borders, BorderType},
     text::{Span, Line},
+    prelude::Alignment,
 };
 
+//STYLE
+pub const TITLE_STYLE: Style = Style::new()
+    .fg(Color::Rgb(158,206,106))
+    .add_modifier(Modifier::BOLD);
+
+pub const BORDER_STYLE: Style = Style::new()
+    .fg(Color::Rgb(86,95,137))
+    .add_modifier(Modifier::BOLD);
+
+pub const HEADER_STYLE: Style = Style::new()
+    .fg(LABEL_COLOR)
+    .add_modifier(Modifier::BOLD);
+
+pub const SELECTED_STYLE: Style = Style::new()
+    .bg(Color::Rgb(41, 46, 66))
+    .add_modifier(Modifier::BOLD);
+
+pub const LABEL_COLOR: Color = Color::Rgb(169, 177, 214);
+pub const OK_COLOR: Color = Color::Rgb(158, 206, 106);
+pub const WARN_COLOR: Color = Color::Rgb(224, 175, 104);
+pub const ALERT_COLOR: Color = Color::Rgb(247, 118, 142);
+
 //metrics converter
 pub fn format_network_rate(rate: f64) -> String
 {
@@ -19,7 +45,26 @@ pub fn format_network_rate(rate: f64) -> String
     }
 }
 
+//Events per second: packets, errors...
+pub fn format_count_rate(rate: f64) -> String
+{
+    if rate >= 1_000_000.0 {
+        format!("{:.1}M/s", rate / 1_000_000.0)
+    } else if rate >= 1_000.0 {
+        format!("{:.1}k/s", rate / 1_000.0)
+    } else if rate > 0.0 && rate < 10.0 {
+        format!("{:.1}/s", rate)
+    } else {
+        format!("{:.0}/s", rate)
+    }
+}
+
 pub fn format_network_total(bytes: u64) -> String
+{
+    format_bytes(bytes)
+}
+
+pub fn format_bytes(bytes: u64) -> String
 {
     let bytes = bytes as f64;
     if bytes >= 1_000_000_000_000.0 {
@@ -35,6 +80,28 @@ pub fn format_network_total(bytes: u64) -> String
     }
 }
 
+//Compact duration: 45s, 3m05s, 2h07m, 3d04h
+pub fn format_duration(secs: u64) -> String
+{
+    match secs {
+        s if s >= 86_400 => format!("{}d{:02}h", s / 86_400, s % 86_400 / 3_600),
+        s if s >= 3_600 => format!("{}h{:02}m", s / 3_600, s % 3_600 / 60),
+        s if s >= 60 => format!("{}m{:02}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    }
+}
+
+//Sub-second precision for short-lived processes: 80ms, 2.35s, then like format_duration
+pub fn format_lifetime(lifetime: Duration) -> String
+{
+    match lifetime.as_millis()
+    {
+        ms if ms < 1_000 => format!("{}ms", ms),
+        ms if ms < 60_000 => format!("{:.2}s", ms as f64 / 1_000.0),
+        _ => format_duration(lifetime.as_secs()),
+    }
+}
+
 pub fn info_line(label: &str, value: &str, color: Color) -> Line<'static> {
     Line::from(vec![
         Span::raw("│ "),
@@ -50,3 +117,17 @@ pub fn info_line(label: &str, value: &str, color: Color) -> Line<'static> {
                ),
     ])
 }
+
+//Rounded block with the " Title " decoration used by every panel
+pub fn panel_block(title: &str) -> Block<'static> {
+    Block::default()
+    .title(Line::from(vec![
+        Span::raw("╭─"),
+                      Span::styled(format!(" {} ", title), TITLE_STYLE),
+                      Span::raw("─╮"),
+    ]))
+    .title_alignment(Alignment::Center)
+    .borders(Borders::ALL)
+    .border_type(BorderType::Rounded)
+    .border_style(BORDER_STYLE)
+}